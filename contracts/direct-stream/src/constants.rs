@@ -1 +1,44 @@
-pub(crate) const ONE_DAY_LEDGERS: u32 = 17280;
\ No newline at end of file
+pub(crate) const ONE_DAY_LEDGERS: u32 = 17280;
+
+/// The scalar for fees expressed in basis points
+pub(crate) const BPS_SCALAR: i128 = 10_000;
+
+/// The scalar for fractions of a segment's duration
+pub(crate) const SEGMENT_SCALAR: i128 = 1_000_000_000_000_000_000;
+
+// Batch limits keep each batch within the 25 ledger entries a transaction can write,
+// leaving room for the token balances, stream id and accrued fees written alongside the streams.
+// Withdrawals and cancellations also write a balance per token or sender refunded, so they
+// count their writes against MAX_BATCH_WRITES as well.
+
+/// The maximum number of ledger entries a batch can write in a single transaction
+pub(crate) const MAX_BATCH_WRITES: u32 = 25;
+
+/// The maximum number of streams that can be created in a single batch
+pub(crate) const MAX_CREATE_BATCH: u32 = 20;
+
+/// The maximum number of streams that can be withdrawn from in a single batch
+pub(crate) const MAX_WITHDRAW_BATCH: u32 = 20;
+
+/// The maximum number of streams that can be cancelled in a single batch
+pub(crate) const MAX_CANCEL_BATCH: u32 = 20;
+
+/// The maximum number of streams whose sender can be transferred in a single batch.
+/// Accepting a transfer writes both the stream and its pending sender.
+pub(crate) const MAX_SENDER_TRANSFER_BATCH: u32 = 12;
+
+/// The scalar used to encode the index of invalid params in a batch into the error code,
+/// as `(index + 1) * BATCH_ERROR_SCALAR + error`
+pub(crate) const BATCH_ERROR_SCALAR: u32 = 1_000;
+
+/// The maximum number of segments a stream can be created with
+pub(crate) const MAX_SEGMENTS: u32 = 50;
+
+/// The maximum number of tranches a stream can be created with
+pub(crate) const MAX_TRANCHES: u32 = 50;
+
+/// The maximum exponent of a convex or concave segment
+pub(crate) const MAX_SEGMENT_EXPONENT: u32 = 8;
+
+/// The maximum fee, in basis points, that can be charged on a stream deposit
+pub(crate) const MAX_FEE_BPS: u32 = 1_000; // 10%
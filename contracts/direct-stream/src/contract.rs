@@ -1,314 +1,1750 @@
 use sep_41_token::TokenClient;
-use soroban_fixed_point_math::{FixedPoint, STROOP};
+use soroban_fixed_point_math::FixedPoint;
 use soroban_sdk::{
-    contract, contractimpl, panic_with_error, unwrap::UnwrapOptimized, Address, Env,
+    contract, contractimpl, panic_with_error, unwrap::UnwrapOptimized, Address, BytesN, Env,
+    Error, Map, Vec, I256,
 };
 
 use crate::{
+    constants::{
+        BATCH_ERROR_SCALAR, BPS_SCALAR, MAX_BATCH_WRITES, MAX_CANCEL_BATCH, MAX_CREATE_BATCH,
+        MAX_FEE_BPS, MAX_SEGMENTS, MAX_SEGMENT_EXPONENT, MAX_SENDER_TRANSFER_BATCH, MAX_TRANCHES,
+        MAX_WITHDRAW_BATCH, SEGMENT_SCALAR,
+    },
     direct_stream::DirectStream,
     errors::StreamError,
     events::StreamEvents,
     storage,
-    types::{DirectStreamData, Status, StreamSettings},
+    types::{
+        DirectStreamData, FeeModel, RangeParams, Role, Segment, SegmentExponent, Status,
+        StreamKind, StreamSettings, Tranche,
+    },
 };
 
+/// Get the amount streamed to the recipient, rounded down in favor of the sender
 fn get_streamed_amount(e: &Env, stream: &DirectStreamData) -> i128 {
-    let current_ledger_time = e.ledger().timestamp();
+    // a cancelled stream stops streaming at the time it was cancelled
+    if stream.is_cancelled {
+        return stream.deposit - stream.refunded;
+    }
+
+    // a paused stream stops streaming at the time it was paused
+    let current_time = if stream.is_paused {
+        stream.paused_time
+    } else {
+        e.ledger().timestamp()
+    };
+
+    match stream.kind {
+        StreamKind::Range => get_range_streamed_amount(e, stream, current_time),
+        StreamKind::Segments => get_segments_streamed_amount(e, stream, current_time),
+        StreamKind::Tranches => get_tranches_streamed_amount(e, stream, current_time),
+    }
+}
 
+/// Get the amount streamed by a range stream at `current_time`
+fn get_range_streamed_amount(e: &Env, stream: &DirectStreamData, current_time: u64) -> i128 {
     // if the cliff time is in the future, return 0
-    if stream.cliff_time > current_ledger_time {
+    if stream.cliff_time > current_time {
         return 0;
     }
 
     // if we have gotten to the end time return the total deposited amount
-    if current_ledger_time >= stream.stop_time {
+    if current_time >= stream.stop_time {
+        return stream.deposit;
+    }
+
+    let elapsed_time = (current_time - stream.start_time) as i128;
+    let total_time = (stream.stop_time - stream.start_time) as i128;
+
+    // the cliff amount is unlocked at the cliff, and the remainder streams linearly. As
+    // elapsed_time < total_time, the result is always strictly less than the deposit
+    let linear_amount = stream.deposit - stream.cliff_amount;
+    stream.cliff_amount + mul_div_floor(e, linear_amount, elapsed_time, total_time)
+}
+
+/// Get the amount streamed by a tranched stream at `current_time`, the sum of the tranches
+/// unlocked so far
+fn get_tranches_streamed_amount(e: &Env, stream: &DirectStreamData, current_time: u64) -> i128 {
+    if current_time >= stream.stop_time {
         return stream.deposit;
     }
 
-    let start_time = stream.start_time as i128;
-    let elapsed_time = (current_ledger_time as i128 - start_time) as i128;
-    let total_time = (stream.stop_time as i128 - start_time) as i128;
+    let mut streamed_amount = 0;
+    for tranche in storage::get_tranches(e, &stream.id).iter() {
+        if current_time < tranche.unlock_time {
+            break;
+        }
+        streamed_amount += tranche.amount;
+    }
+    streamed_amount
+}
+
+/// Get the number of tranches of `stream` unlocked so far, 0 if it is not a tranched stream.
+/// A cancelled stream keeps the tranches that were unlocked before it was cancelled.
+fn get_unlocked_tranches(e: &Env, stream: &DirectStreamData) -> u32 {
+    if stream.kind != StreamKind::Tranches {
+        return 0;
+    }
 
-    let elapsed_time_percent = elapsed_time
-        .fixed_div_floor(total_time, STROOP as i128)
-        .unwrap();
+    let current_time = if stream.is_paused {
+        stream.paused_time
+    } else {
+        e.ledger().timestamp()
+    };
+    let cancelled_amount = stream.deposit - stream.refunded;
 
-    let streamed_amount = stream
-        .deposit
-        .fixed_mul_floor(elapsed_time_percent, STROOP as i128)
-        .unwrap();
+    let mut unlocked = 0;
+    let mut streamed_amount = 0;
+    for tranche in storage::get_tranches(e, &stream.id).iter() {
+        // the refunded tranches of a cancelled stream never unlock
+        if stream.is_cancelled {
+            if streamed_amount + tranche.amount > cancelled_amount {
+                break;
+            }
+        } else if current_time < tranche.unlock_time {
+            break;
+        }
+        streamed_amount += tranche.amount;
+        unlocked += 1;
+    }
+    unlocked
+}
 
-    // This is to prevent a bug from happening where the streamed amount is greater than the deposit
-    // [TODO] Test this extensively to make sure it works as expected and decide whether to use amount deposited or withdrawn.
-    if streamed_amount > stream.deposit {
+/// Get the amount streamed by a segmented stream at `current_time`. Each segment streams
+/// its amount between the end of the previous segment and its own end time.
+fn get_segments_streamed_amount(e: &Env, stream: &DirectStreamData, current_time: u64) -> i128 {
+    if current_time <= stream.start_time {
+        return 0;
+    }
+    if current_time >= stream.stop_time {
         return stream.deposit;
     }
 
+    let mut streamed_amount = 0;
+    let mut segment_start_time = stream.start_time;
+    for segment in storage::get_segments(e, &stream.id).iter() {
+        if current_time < segment.end_time {
+            let elapsed_time = (current_time - segment_start_time) as i128;
+            let total_time = (segment.end_time - segment_start_time) as i128;
+            return streamed_amount + get_segment_amount(e, &segment, elapsed_time, total_time);
+        }
+        streamed_amount += segment.amount;
+        segment_start_time = segment.end_time;
+    }
     streamed_amount
 }
 
-/*
-Modifiers for the contract
-*/
-fn require_sender_or_recipient(stream: &DirectStreamData, user: &Address) {
-    let sender = &(stream.sender);
-    let recipient = &(stream.recipient);
-    assert!(
-        user == sender || user == recipient,
-        "only sender or recipient can call this function"
-    );
+/// Get the amount streamed by a segment `elapsed_time` into its `total_time`, rounded down
+fn get_segment_amount(e: &Env, segment: &Segment, elapsed_time: i128, total_time: i128) -> i128 {
+    let elapsed_fraction = mul_div_floor(e, SEGMENT_SCALAR, elapsed_time, total_time);
+    let streamed_fraction = match segment.exponent {
+        SegmentExponent::Linear => {
+            return mul_div_floor(e, segment.amount, elapsed_time, total_time);
+        }
+        SegmentExponent::Convex(exponent) => fixed_pow(elapsed_fraction, exponent, false),
+        // round the remaining fraction up so the streamed fraction is rounded down
+        SegmentExponent::Concave(exponent) => {
+            SEGMENT_SCALAR - fixed_pow(SEGMENT_SCALAR - elapsed_fraction, exponent, true)
+        }
+    };
+    mul_div_floor(e, segment.amount, streamed_fraction, SEGMENT_SCALAR)
 }
 
-fn require_recipient(stream: &DirectStreamData, user: &Address) {
-    let recipient = &(stream.recipient);
-    assert!(
-        user == recipient,
-        "only recipient can receive the streamed amount"
-    );
+/// Raise `x`, a fraction scaled by `SEGMENT_SCALAR` between 0 and 1, to the power of `exponent`
+fn fixed_pow(x: i128, exponent: u32, round_up: bool) -> i128 {
+    let mut result = SEGMENT_SCALAR;
+    for _ in 0..exponent {
+        // both values are at most SEGMENT_SCALAR, so the product cannot overflow
+        result = if round_up {
+            result.fixed_mul_ceil(x, SEGMENT_SCALAR)
+        } else {
+            result.fixed_mul_floor(x, SEGMENT_SCALAR)
+        }
+        .unwrap_optimized();
+    }
+    result
 }
 
-// Transfer tokens from the contract to the recipient
-fn transfer(e: &Env, from: &Address, to: &Address, amount: &i128, token_address: &Address) {
-    let token_client = TokenClient::new(e, &token_address);
-    token_client.transfer(from, to, amount);
+/// Performs floor(x * y / z) for non-negative `x` and `y` and positive `z`, widening to
+/// 256 bits if the intermediate product does not fit in an i128
+fn mul_div_floor(e: &Env, x: i128, y: i128, z: i128) -> i128 {
+    match x.fixed_mul_floor(y, z) {
+        Some(result) => result,
+        None => I256::from_i128(e, x)
+            .mul(&I256::from_i128(e, y))
+            .div(&I256::from_i128(e, z))
+            .to_i128()
+            .unwrap_or_else(|| panic_with_error!(e, StreamError::OverflowError)),
+    }
 }
 
-#[contract]
-pub struct DirectStreamContract;
+/// Require the remaining deposit of a range stream to unlock at least as fast once
+/// `amount` is added and the stop time moved to `new_stop_time`, so a top up never delays
+/// what the recipient was already due
+fn require_unlock_rate_kept(e: &Env, stream: &DirectStreamData, amount: i128, new_stop_time: u64) {
+    // the linear part of the schedule restarts from now once past the cliff
+    let current_time = e.ledger().timestamp();
+    let (unlocked_amount, from_time) = if current_time >= stream.cliff_time {
+        (get_streamed_amount(e, stream), current_time)
+    } else {
+        (stream.cliff_amount, stream.start_time)
+    };
 
-#[contractimpl]
-#[allow(clippy::needless_pass_by_value)]
-impl DirectStream for DirectStreamContract {
-    fn initialize(e: Env, settings: StreamSettings) {
-        if storage::get_is_init(&e) {
-            panic_with_error!(&e, StreamError::AlreadyInitializedError);
+    // (remaining + amount) / (new_stop_time - from) >= remaining / (stop_time - from)
+    let remaining = I256::from_i128(e, stream.deposit - unlocked_amount);
+    let new_rate = remaining
+        .add(&I256::from_i128(e, amount))
+        .mul(&I256::from_i128(e, (stream.stop_time - from_time) as i128));
+    let rate = remaining.mul(&I256::from_i128(e, (new_stop_time - from_time) as i128));
+    if new_rate < rate {
+        panic_with_error!(e, StreamError::InvalidStopTime);
+    }
+}
+
+/// Require a batch to write no more ledger entries than a transaction allows
+fn require_batch_writes(e: &Env, writes: u32) {
+    if writes > MAX_BATCH_WRITES {
+        panic_with_error!(e, StreamError::InvalidBatch);
+    }
+}
+
+/// Get the amount streamed to the recipient that has not been withdrawn yet
+fn get_withdrawable_amount(e: &Env, stream: &DirectStreamData) -> i128 {
+    get_streamed_amount(e, stream) - stream.withdrawn
+}
+
+/// Calculate the fee for a stream of `amount` of `token_address`, falling back to the
+/// settings `base_fee` if the token doesn't have a fee set
+///
+/// The fee never exceeds `MAX_FEE_BPS` of `amount`, regardless of the fee model
+fn get_fee(e: &Env, token_address: &Address, amount: i128) -> i128 {
+    let fee_model = storage::get_asset_fee(e, token_address)
+        .unwrap_or_else(|| storage::get_settings(e).base_fee);
+
+    let fee = match fee_model {
+        FeeModel::Flat(fee) => fee,
+        FeeModel::Bps(bps) => get_bps_fee(e, amount, bps),
+        FeeModel::ClampedBps(bps, min_fee, max_fee) => {
+            get_bps_fee(e, amount, bps).clamp(min_fee, max_fee)
         }
+    };
+    fee.min(get_max_fee(amount))
+}
 
-        storage::set_is_init(&e);
-        storage::set_settings(&e, &settings);
-        storage::extend_instance(&e);
+/// Calculate `MAX_FEE_BPS` basis points of `amount`, rounding down. The whole and
+/// partial multiples of the scalar are handled separately so large amounts can't overflow.
+fn get_max_fee(amount: i128) -> i128 {
+    let max_fee_bps = MAX_FEE_BPS as i128;
+    amount / BPS_SCALAR * max_fee_bps + amount % BPS_SCALAR * max_fee_bps / BPS_SCALAR
+}
+
+/// Calculate `bps` basis points of `amount`, rounding in favor of the protocol
+fn get_bps_fee(e: &Env, amount: i128, bps: u32) -> i128 {
+    amount
+        .fixed_mul_ceil(bps as i128, BPS_SCALAR)
+        .unwrap_or_else(|| panic_with_error!(e, StreamError::OverflowError))
+}
+
+/// Validate a fee model against the protocol limits
+fn require_valid_fee_model(e: &Env, fee_model: &FeeModel) {
+    match fee_model {
+        FeeModel::Flat(fee) => {
+            if *fee < 0 {
+                panic_with_error!(e, StreamError::NegativeAmountError);
+            }
+        }
+        FeeModel::Bps(bps) => {
+            if *bps > MAX_FEE_BPS {
+                panic_with_error!(e, StreamError::FeeExceedsMaximum);
+            }
+        }
+        FeeModel::ClampedBps(bps, min_fee, max_fee) => {
+            if *bps > MAX_FEE_BPS {
+                panic_with_error!(e, StreamError::FeeExceedsMaximum);
+            }
+            if *min_fee < 0 {
+                panic_with_error!(e, StreamError::NegativeAmountError);
+            }
+            if min_fee > max_fee {
+                panic_with_error!(e, StreamError::InvalidFeeModel);
+            }
+        }
     }
+}
 
-    fn settings(e: Env) -> StreamSettings {
-        storage::get_settings(&e)
+/*
+Modifiers for the contract
+*/
+fn require_sender_or_recipient(e: &Env, stream: &DirectStreamData, user: &Address) {
+    if *user != stream.sender && *user != stream.recipient {
+        panic_with_error!(e, StreamError::NotSenderOrRecipient);
     }
+}
 
-    ///
+/// Require the user to be the sender or recipient of the stream, or an operator approved
+/// by the recipient to withdraw on their behalf
+///
+/// While the contract is paused, only the recipient can withdraw
+fn require_withdrawer(e: &Env, stream: &DirectStreamData, user: &Address) {
+    if *user != stream.sender
+        && *user != stream.recipient
+        && !is_active_operator(e, &stream.recipient, user)
+    {
+        panic_with_error!(e, StreamError::NotSenderOrRecipient);
+    }
+    // recipients can always withdraw, even while withdrawals are paused
+    if *user != stream.recipient && storage::get_withdrawals_paused(e) {
+        panic_with_error!(e, StreamError::ContractPaused);
+    }
+}
 
-    fn get_stream(e: Env, stream_id: u32) -> Option<DirectStreamData> {
-        storage::get_stream(&e, &stream_id)
+fn require_not_paused(e: &Env) {
+    if storage::get_paused(e) {
+        panic_with_error!(e, StreamError::ContractPaused);
     }
+}
 
-    /// Returns the amount of tokens that have already been released to the recipient.
-    /// Panics if the id does not point to a valid stream.
-    /// @param stream_id The id of the stream
-    /// @param who The address of the caller
-    /// @return The amount of tokens that have already been released
-    fn streamed_amount(e: Env, stream_id: u32) -> i128 {
-        let stream = storage::get_stream(&e, &stream_id);
+/// Check if `account` is the admin or has been granted `role`
+fn holds_role(e: &Env, role: &Role, account: &Address) -> bool {
+    *account == storage::get_settings(e).admin || storage::has_role(e, role, account)
+}
 
-        if stream.is_none() {
-            panic_with_error!(&e, StreamError::StreamDoesNotExist);
-        } else {
-            get_streamed_amount(&e, &stream.unwrap_optimized())
-        }
+/// Require the caller to be the admin or hold `role`
+fn require_role(e: &Env, role: Role, caller: &Address) {
+    caller.require_auth();
+    if !holds_role(e, &role, caller) {
+        panic_with_error!(e, StreamError::UnauthorizedError);
     }
+}
 
-    /// Returns the status of the stream.
-    fn status(e: Env, stream_id: u32) -> Option<Status> {
-        let stream_resp = storage::get_stream(&e, &stream_id);
+fn require_recipient(e: &Env, stream: &DirectStreamData, user: &Address) {
+    if *user != stream.recipient {
+        panic_with_error!(e, StreamError::NotRecipient);
+    }
+}
 
-        if stream_resp.is_none() {
-            return None;
-        }
+fn require_positive_amount(e: &Env, amount: i128) {
+    if amount <= 0 {
+        panic_with_error!(e, StreamError::InvalidAmount);
+    }
+}
 
-        let stream = stream_resp.unwrap_optimized();
+/// Check the amounts and schedule of a range stream are valid
+fn check_range_params(e: &Env, params: &RangeParams) -> Result<(), StreamError> {
+    if params.amount <= 0 {
+        return Err(StreamError::InvalidAmount);
+    }
+    if params.start_time < e.ledger().timestamp() {
+        return Err(StreamError::StartTimeInPast);
+    }
+    if params.cliff_time < params.start_time {
+        return Err(StreamError::CliffBeforeStart);
+    }
+    if params.stop_time <= params.start_time {
+        return Err(StreamError::StopBeforeStart);
+    }
+    if params.stop_time <= params.cliff_time {
+        return Err(StreamError::StopBeforeCliff);
+    }
+    if params.cliff_amount < 0 || params.cliff_amount > params.amount {
+        return Err(StreamError::InvalidCliffAmount);
+    }
+    Ok(())
+}
 
-        let current_ledger_time = e.ledger().timestamp();
+/// Get the error a batch fails with when the params at `index` are invalid, encoding the
+/// index alongside the underlying error so it is visible on-chain
+fn get_batch_error(index: u32, error: StreamError) -> Error {
+    Error::from_contract_error((index + 1) * BATCH_ERROR_SCALAR + error as u32)
+}
 
-        if stream.is_depleted {
-            return Some(Status::Depleted);
-        }
+/// Build a new range stream from `sender` of `token_address` with `params`
+fn new_range_stream(
+    sender: Address,
+    token_address: Address,
+    params: RangeParams,
+) -> DirectStreamData {
+    DirectStreamData {
+        id: 0,
+        kind: StreamKind::Range,
+        sender,
+        recipient: params.recipient,
+        start_time: params.start_time,
+        stop_time: params.stop_time,
+        cliff_time: params.cliff_time,
+        deposit: params.amount,
+        cliff_amount: params.cliff_amount,
+        fee: 0,
+        is_cancelled: false,
+        is_cancellable: params.cancellable,
+        is_transferable: params.transferable,
+        is_depleted: false,
+        is_paused: false,
+        paused_time: 0,
+        refunded: 0,
+        withdrawn: 0,
+        token_address,
+    }
+}
 
-        if stream.is_cancelled {
-            return Some(Status::Cancelled);
-        }
+/// Require the segments to be non-empty, ordered by end time after `start_time` and to
+/// sum to `amount`
+///
+/// Returns the end time of the last segment
+fn require_valid_segments(e: &Env, amount: i128, start_time: u64, segments: &Vec<Segment>) -> u64 {
+    if start_time < e.ledger().timestamp() {
+        panic_with_error!(e, StreamError::StartTimeInPast);
+    }
+    if segments.is_empty() || segments.len() > MAX_SEGMENTS {
+        panic_with_error!(e, StreamError::InvalidSegments);
+    }
 
-        if current_ledger_time < stream.start_time {
-            return Some(Status::Pending);
+    let mut total_amount: i128 = 0;
+    let mut previous_end_time = start_time;
+    for segment in segments.iter() {
+        if segment.amount < 0 {
+            panic_with_error!(e, StreamError::InvalidSegments);
         }
-
-        if current_ledger_time < stream.cliff_time {
-            return Some(Status::Cliff);
+        if let SegmentExponent::Convex(exponent) | SegmentExponent::Concave(exponent) =
+            segment.exponent
+        {
+            if !(2..=MAX_SEGMENT_EXPONENT).contains(&exponent) {
+                panic_with_error!(e, StreamError::InvalidSegments);
+            }
         }
+        if segment.end_time <= previous_end_time {
+            panic_with_error!(e, StreamError::SegmentsNotOrdered);
+        }
+        total_amount = total_amount
+            .checked_add(segment.amount)
+            .unwrap_or_else(|| panic_with_error!(e, StreamError::OverflowError));
+        previous_end_time = segment.end_time;
+    }
 
-        let streamed_amount = get_streamed_amount(&e, &stream);
+    if total_amount != amount {
+        panic_with_error!(e, StreamError::SegmentAmountsMismatch);
+    }
+    previous_end_time
+}
 
-        if streamed_amount >= stream.deposit {
-            return Some(Status::Completed);
+/// Require the tranches to be non-empty, ordered by unlock time after `start_time` and to
+/// sum to `amount`
+///
+/// Returns the unlock time of the last tranche
+fn require_valid_tranches(e: &Env, amount: i128, start_time: u64, tranches: &Vec<Tranche>) -> u64 {
+    if start_time < e.ledger().timestamp() {
+        panic_with_error!(e, StreamError::StartTimeInPast);
+    }
+    if tranches.is_empty() || tranches.len() > MAX_TRANCHES {
+        panic_with_error!(e, StreamError::InvalidTranches);
+    }
+
+    let mut total_amount: i128 = 0;
+    let mut previous_unlock_time = start_time;
+    for tranche in tranches.iter() {
+        if tranche.amount < 0 {
+            panic_with_error!(e, StreamError::InvalidTranches);
         }
+        if tranche.unlock_time <= previous_unlock_time {
+            panic_with_error!(e, StreamError::TranchesNotOrdered);
+        }
+        total_amount = total_amount
+            .checked_add(tranche.amount)
+            .unwrap_or_else(|| panic_with_error!(e, StreamError::OverflowError));
+        previous_unlock_time = tranche.unlock_time;
+    }
 
-        Some(Status::Active)
+    if total_amount != amount {
+        panic_with_error!(e, StreamError::TrancheAmountsMismatch);
     }
+    previous_unlock_time
+}
 
-    fn create_range(
-        e: Env,
-        sender: Address,
-        recipient: Address,
-        amount: i128,
-        token_address: Address,
-        start_time: u64,
-        stop_time: u64,
-        cancellable: bool,
-        cliff_time: u64,
-    ) -> u32 {
-        sender.require_auth();
-        assert!(amount > 0, "amount is zero or negative");
-        assert!(
-            start_time >= e.ledger().timestamp(),
-            "start time before current ledger timestamp"
-        );
-        assert!(cliff_time >= start_time, "cliff time before the start time");
-        assert!(stop_time > start_time, "stop time before the start time");
-        assert!(stop_time > cliff_time, "stop time before the cliff time");
+/// Require `to` to be a valid destination for a withdrawal made by `caller`. The
+/// recipient can withdraw to any address, while the sender can only withdraw to the recipient.
+fn require_valid_destination(e: &Env, stream: &DirectStreamData, caller: &Address, to: &Address) {
+    if *to == e.current_contract_address() {
+        panic_with_error!(e, StreamError::InvalidDestination);
+    }
+    if *caller != stream.recipient {
+        require_recipient(e, stream, to);
+    }
+}
 
-        let stream_id = storage::get_next_stream_id(&e);
-        let fee = storage::get_asset_fee(&e, &token_address).unwrap_or(0);
+/// Withdraw `amount` from `stream` to `to` and mark the stream as depleted once it is
+/// fully settled
+fn withdraw_from_stream(e: &Env, stream: &mut DirectStreamData, to: &Address, amount: i128) {
+    record_withdrawal(e, stream, amount);
 
-        let total_amount = amount + fee;
+    transfer(
+        e,
+        &e.current_contract_address(),
+        to,
+        &amount,
+        &stream.token_address,
+    );
 
-        // transfer the token to the contract
-        transfer(
-            &e,
-            &sender,
-            &e.current_contract_address(),
-            &total_amount,
-            &token_address,
-        );
+    StreamEvents::stream_withdrawn(e, to.clone(), stream.id, amount);
+}
 
-        let stream = DirectStreamData {
-            id: stream_id.clone(),
-            sender: sender.clone(),
-            recipient: recipient.clone(),
-            start_time: start_time,
-            stop_time: stop_time,
-            cliff_time: cliff_time,
-            deposit: amount,
-            is_cancelled: false,
-            is_cancellable: cancellable,
-            is_depleted: false,
-            refunded: 0,
-            withdrawn: 0,
-            token_address: token_address.clone(),
-        };
+/// Mark `stream` as cancelled without transferring the refund, and return the
+/// unstreamed amount owed to the sender
+fn settle_cancellation(e: &Env, stream: &mut DirectStreamData) -> i128 {
+    let streamed_amount = get_streamed_amount(e, stream);
+    let sender_balance = stream.deposit - streamed_amount;
 
-        let next_stream_id = stream_id + 1;
+    stream.is_cancelled = true;
+    stream.refunded = sender_balance;
+    if stream.withdrawn == streamed_amount {
+        stream.is_depleted = true;
+    }
 
-        storage::set_stream(&e, &stream_id, &stream);
-        storage::set_next_stream_id(&e, next_stream_id);
+    storage::set_stream(e, &stream.id, stream);
+    sender_balance
+}
 
-        StreamEvents::stream_created(
-            &e,
-            stream_id.clone(),
-            sender,
-            recipient,
-            amount,
-            token_address,
-            start_time,
-            stop_time,
-        );
-        stream_id
+/// Record a withdrawal of `amount` from `stream` without transferring the tokens,
+/// marking the stream as depleted once it is fully settled
+fn record_withdrawal(e: &Env, stream: &mut DirectStreamData, amount: i128) {
+    stream.withdrawn += amount;
+    if stream.withdrawn + stream.refunded == stream.deposit {
+        stream.is_depleted = true;
     }
 
-    fn withdraw(
-        e: Env,
-        caller: Address,
-        recipient: Address,
-        stream_id: u32,
-        amount: i128,
-    ) {
-        caller.require_auth();
-        assert!(amount > 0, "amount is zero or negative");
-        assert!(
-            recipient != e.current_contract_address(),
-            "stream to the contract itself"
-        );
+    storage::set_stream(e, &stream.id, stream);
+}
 
-        let stream_resp = storage::get_stream(&e, &stream_id);
+/// Charge the fee for `stream`, transfer the deposit and fee from the sender and store the
+/// stream under the next stream id
+///
+/// Returns the id of the new stream
+fn create_stream(e: &Env, mut stream: DirectStreamData) -> u32 {
+    let fee = get_fee(e, &stream.token_address, stream.deposit);
 
-        if stream_resp.is_none() {
-            panic_with_error!(&e, StreamError::StreamDoesNotExist);
-        }
+    let total_amount = stream.deposit + fee;
 
-        let mut stream = stream_resp.unwrap_optimized();
+    // transfer the token to the contract
+    transfer(
+        e,
+        &stream.sender,
+        &e.current_contract_address(),
+        &total_amount,
+        &stream.token_address,
+    );
 
-        require_sender_or_recipient(&stream, &caller);
-        require_recipient(&stream, &recipient);
+    stream.fee = fee;
+    accrue_fee(e, &stream.token_address, fee);
 
-        let streamed_amount = get_streamed_amount(&e, &stream);
-        if amount > streamed_amount {
-            panic_with_error!(&e, StreamError::ExceedsStreamedAmount);
-        };
+    store_new_stream(e, stream)
+}
 
-        stream.withdrawn = stream.withdrawn + amount;
+/// Store a funded stream under the next stream id
+///
+/// Returns the id of the new stream
+fn store_new_stream(e: &Env, mut stream: DirectStreamData) -> u32 {
+    let stream_id = storage::get_next_stream_id(e);
+    stream.id = stream_id;
 
-        storage::set_stream(&e, &stream_id, &stream);
+    let next_stream_id = stream_id + 1;
 
-        transfer(
-            &e,
-            &e.current_contract_address(),
-            &recipient,
-            &amount,
-            &stream.token_address,
-        );
+    storage::set_stream(e, &stream_id, &stream);
+    storage::set_next_stream_id(e, next_stream_id);
+
+    StreamEvents::stream_created(
+        e,
+        stream_id,
+        stream.sender,
+        stream.recipient,
+        stream.deposit,
+        stream.fee,
+        stream.token_address,
+        stream.start_time,
+        stream.stop_time,
+    );
+    stream_id
+}
 
-        StreamEvents::stream_withdrawn(&e, recipient, stream_id, amount);
+/// Add `fee` to the fees accrued by the protocol for `token_address`
+fn accrue_fee(e: &Env, token_address: &Address, fee: i128) {
+    if fee > 0 {
+        let accrued_fees = storage::get_accrued_fees(e, token_address);
+        storage::set_accrued_fees(e, token_address, &(accrued_fees + fee));
     }
+}
 
-    /// Cancels the stream and transfers the tokens back on a pro rata basis.
-    /// Throws if the id does not point to a valid stream.
-    /// Throws if the caller is not the sender or the recipient of the stream.
-    /// Throws if there is a token transfer failure.
-    /// @param stream_id The id of the stream to cancel.
-    /// @return bool true=success, otherwise false.
-    fn cancel(e: Env, caller: Address, stream_id: u32) {
-        caller.require_auth();
-        let stream = storage::get_stream(&e, &stream_id);
+/// Shift the schedule of `stream` forward by `duration`, including the segments or
+/// tranches of the stream
+fn shift_schedule(e: &Env, stream: &mut DirectStreamData, duration: u64) {
+    stream.start_time += duration;
+    stream.cliff_time += duration;
+    stream.stop_time += duration;
 
-        if stream.is_none() {
-            panic_with_error!(&e, StreamError::StreamDoesNotExist);
+    match stream.kind {
+        StreamKind::Range => {}
+        StreamKind::Segments => {
+            let mut segments = storage::get_segments(e, &stream.id);
+            for i in 0..segments.len() {
+                let mut segment = segments.get_unchecked(i);
+                segment.end_time += duration;
+                segments.set(i, segment);
+            }
+            storage::set_segments(e, &stream.id, &segments);
         }
+        StreamKind::Tranches => {
+            let mut tranches = storage::get_tranches(e, &stream.id);
+            for i in 0..tranches.len() {
+                let mut tranche = tranches.get_unchecked(i);
+                tranche.unlock_time += duration;
+                tranches.set(i, tranche);
+            }
+            storage::set_tranches(e, &stream.id, &tranches);
+        }
+    }
+}
 
-        let mut stream = stream.unwrap_optimized();
-        require_sender_or_recipient(&stream, &caller);
+/// Move the recipient rights of `stream` from `from` to `to`, clearing any approval
+fn transfer_stream(e: &Env, stream: &mut DirectStreamData, from: &Address, to: &Address) {
+    if !stream.is_transferable {
+        panic_with_error!(e, StreamError::NotTransferable);
+    }
+    require_recipient(e, stream, from);
+    // the contract can't withdraw, so a stream transferred to it would be locked for good
+    if *to == e.current_contract_address() {
+        panic_with_error!(e, StreamError::InvalidDestination);
+    }
 
-        let streamed_amount = get_streamed_amount(&e, &stream);
-        let recipient_balance = streamed_amount - stream.withdrawn;
-        let sender_balance = stream.deposit - streamed_amount;
+    stream.recipient = to.clone();
+    storage::set_stream(e, &stream.id, stream);
+    storage::set_approval(e, &stream.id, &None);
+
+    StreamEvents::transfer(e, from.clone(), to.clone(), stream.id);
+}
 
-        stream.is_cancelled = true;
+/// Propose `new_sender` to take over as sender of `stream`, replacing any previous proposal
+fn propose_sender(e: &Env, stream: &DirectStreamData, new_sender: &Address) {
+    storage::set_pending_sender(e, &stream.id, &Some(new_sender.clone()));
 
-        storage::set_stream(&e, &stream_id, &stream);
+    StreamEvents::sender_transfer_proposed(
+        e,
+        stream.id,
+        stream.sender.clone(),
+        new_sender.clone(),
+    );
+}
 
-        if recipient_balance > 0 {
-            transfer(
-                &e,
-                &e.current_contract_address(),
-                &stream.recipient,
-                &recipient_balance,
-                &stream.token_address,
-            );
-        }
-        if sender_balance > 0 {
-            transfer(
-                &e,
-                &e.current_contract_address(),
-                &stream.sender,
-                &sender_balance,
-                &stream.token_address,
-            );
-        }
+/// Make `new_sender` the sender of `stream` if it is the proposed sender
+fn accept_sender(e: &Env, stream: &mut DirectStreamData, new_sender: &Address) {
+    if storage::get_pending_sender(e, &stream.id) != Some(new_sender.clone()) {
+        panic_with_error!(e, StreamError::NotPendingSender);
+    }
 
-        StreamEvents::stream_cancelled(&e, stream_id);
+    let old_sender = stream.sender.clone();
+    stream.sender = new_sender.clone();
+    storage::set_stream(e, &stream.id, stream);
+    storage::set_pending_sender(e, &stream.id, &None);
+
+    StreamEvents::sender_transferred(e, stream.id, old_sender, new_sender.clone());
+}
+
+/// Check if `operator` is approved by `recipient` to withdraw and the approval has not expired
+fn is_active_operator(e: &Env, recipient: &Address, operator: &Address) -> bool {
+    match storage::get_withdraw_operator(e, recipient, operator) {
+        Some(expiry) => expiry > e.ledger().timestamp(),
+        None => false,
+    }
+}
+
+// Transfer tokens from the contract to the recipient
+fn transfer(e: &Env, from: &Address, to: &Address, amount: &i128, token_address: &Address) {
+    let token_client = TokenClient::new(e, token_address);
+    token_client.transfer(from, to, amount);
+}
+
+#[contract]
+pub struct DirectStreamContract;
+
+#[contractimpl]
+#[allow(clippy::needless_pass_by_value)]
+impl DirectStream for DirectStreamContract {
+    fn initialize(e: Env, settings: StreamSettings) {
+        if storage::get_is_init(&e) {
+            panic_with_error!(&e, StreamError::AlreadyInitializedError);
+        }
+        require_valid_fee_model(&e, &settings.base_fee);
+
+        storage::set_is_init(&e);
+        storage::set_settings(&e, &settings);
+        storage::extend_instance(&e);
+    }
+
+    fn settings(e: Env) -> StreamSettings {
+        storage::get_settings(&e)
+    }
+
+    fn pending_admin(e: Env) -> Option<Address> {
+        storage::get_pending_admin(&e)
+    }
+
+    fn propose_admin(e: Env, new_admin: Address) {
+        let settings = storage::get_settings(&e);
+        settings.admin.require_auth();
+        storage::extend_instance(&e);
+
+        storage::set_pending_admin(&e, &Some(new_admin.clone()));
+
+        StreamEvents::admin_proposed(&e, settings.admin, new_admin);
+    }
+
+    fn accept_admin(e: Env) {
+        let new_admin = storage::get_pending_admin(&e)
+            .unwrap_or_else(|| panic_with_error!(&e, StreamError::NoPendingAdmin));
+        new_admin.require_auth();
+        storage::extend_instance(&e);
+
+        let mut settings = storage::get_settings(&e);
+        let old_admin = settings.admin;
+        settings.admin = new_admin.clone();
+        storage::set_settings(&e, &settings);
+        storage::set_pending_admin(&e, &None);
+
+        StreamEvents::admin_changed(&e, old_admin, new_admin);
+    }
+
+    fn has_role(e: Env, role: Role, account: Address) -> bool {
+        holds_role(&e, &role, &account)
+    }
+
+    fn grant_role(e: Env, role: Role, account: Address) {
+        let settings = storage::get_settings(&e);
+        settings.admin.require_auth();
+        storage::extend_instance(&e);
+
+        storage::set_role(&e, &role, &account, true);
+
+        StreamEvents::role_granted(&e, account, role);
+    }
+
+    fn revoke_role(e: Env, role: Role, account: Address) {
+        let settings = storage::get_settings(&e);
+        settings.admin.require_auth();
+        storage::extend_instance(&e);
+
+        storage::set_role(&e, &role, &account, false);
+
+        StreamEvents::role_revoked(&e, account, role);
+    }
+
+    fn upgrade(e: Env, caller: Address, wasm_hash: BytesN<32>) {
+        require_role(&e, Role::Upgrader, &caller);
+        storage::extend_instance(&e);
+
+        e.deployer().update_current_contract_wasm(wasm_hash.clone());
+
+        StreamEvents::contract_upgraded(&e, caller, wasm_hash);
+    }
+
+    fn paused(e: Env) -> bool {
+        storage::get_paused(&e)
+    }
+
+    fn withdrawals_paused(e: Env) -> bool {
+        storage::get_withdrawals_paused(&e)
+    }
+
+    fn set_paused(e: Env, caller: Address, paused: bool, pause_withdrawals: bool) {
+        require_role(&e, Role::Pauser, &caller);
+        storage::extend_instance(&e);
+
+        // withdrawals can only be paused alongside the contract
+        let pause_withdrawals = paused && pause_withdrawals;
+        storage::set_paused(&e, paused);
+        storage::set_withdrawals_paused(&e, pause_withdrawals);
+
+        if paused {
+            StreamEvents::contract_paused(&e, caller, pause_withdrawals);
+        } else {
+            StreamEvents::contract_unpaused(&e, caller);
+        }
+    }
+
+    fn set_asset_fee(e: Env, caller: Address, token_address: Address, fee: FeeModel) {
+        require_role(&e, Role::FeeManager, &caller);
+        storage::extend_instance(&e);
+
+        require_valid_fee_model(&e, &fee);
+
+        storage::set_asset_fee(&e, &token_address, &fee);
+
+        StreamEvents::asset_fee_set(&e, token_address, fee);
+    }
+
+    fn remove_asset_fee(e: Env, caller: Address, token_address: Address) {
+        require_role(&e, Role::FeeManager, &caller);
+        storage::extend_instance(&e);
+
+        if storage::get_asset_fee(&e, &token_address).is_none() {
+            panic_with_error!(&e, StreamError::AssetFeeNotSet);
+        }
+
+        storage::del_asset_fee(&e, &token_address);
+
+        StreamEvents::asset_fee_removed(&e, token_address);
+    }
+
+    fn asset_fee(e: Env, token_address: Address) -> Option<FeeModel> {
+        storage::get_asset_fee(&e, &token_address)
+    }
+
+    fn migrate_asset_fee(e: Env, caller: Address, token_address: Address) {
+        require_role(&e, Role::FeeManager, &caller);
+        storage::extend_instance(&e);
+
+        let fee = match storage::get_legacy_asset_fee(&e, &token_address) {
+            Some(fee) => fee,
+            None => panic_with_error!(&e, StreamError::AssetFeeNotSet),
+        };
+
+        storage::set_asset_fee(&e, &token_address, &fee);
+        storage::del_legacy_asset_fee(&e, &token_address);
+
+        StreamEvents::asset_fee_migrated(&e, token_address, fee);
+    }
+
+    fn quote_fee(e: Env, token_address: Address, amount: i128) -> i128 {
+        require_positive_amount(&e, amount);
+
+        get_fee(&e, &token_address, amount)
+    }
+
+    fn claim_fees(e: Env, token_address: Address, to: Address, amount: i128) {
+        let settings = storage::get_settings(&e);
+        settings.admin.require_auth();
+
+        require_positive_amount(&e, amount);
+
+        // fees are only ever paid out of the accrued fee ledger, never out of stream balances
+        let accrued_fees = storage::get_accrued_fees(&e, &token_address);
+        if amount > accrued_fees {
+            panic_with_error!(&e, StreamError::InsufficientAccruedFees);
+        }
+
+        storage::set_accrued_fees(&e, &token_address, &(accrued_fees - amount));
+
+        transfer(
+            &e,
+            &e.current_contract_address(),
+            &to,
+            &amount,
+            &token_address,
+        );
+
+        StreamEvents::fees_claimed(&e, token_address, to, amount);
+    }
+
+    fn accrued_fees(e: Env, token_address: Address) -> i128 {
+        storage::get_accrued_fees(&e, &token_address)
+    }
+
+    fn get_stream(e: Env, stream_id: u32) -> Option<DirectStreamData> {
+        storage::get_stream(&e, &stream_id)
+    }
+
+    /// Returns the amount of tokens that have already been released to the recipient.
+    /// Panics if the id does not point to a valid stream.
+    /// @param stream_id The id of the stream
+    /// @param who The address of the caller
+    /// @return The amount of tokens that have already been released
+    fn streamed_amount(e: Env, stream_id: u32) -> i128 {
+        let stream = storage::get_stream(&e, &stream_id);
+
+        if stream.is_none() {
+            panic_with_error!(&e, StreamError::StreamDoesNotExist);
+        } else {
+            get_streamed_amount(&e, &stream.unwrap_optimized())
+        }
+    }
+
+    /// Returns the amount of tokens that can currently be withdrawn by the recipient.
+    /// Panics if the id does not point to a valid stream.
+    fn withdrawable_amount(e: Env, stream_id: u32) -> i128 {
+        let stream = storage::get_stream(&e, &stream_id);
+
+        if stream.is_none() {
+            panic_with_error!(&e, StreamError::StreamDoesNotExist);
+        } else {
+            get_withdrawable_amount(&e, &stream.unwrap_optimized())
+        }
+    }
+
+    fn unlocked_tranches(e: Env, stream_id: u32) -> u32 {
+        let stream = storage::get_stream(&e, &stream_id);
+
+        if stream.is_none() {
+            panic_with_error!(&e, StreamError::StreamDoesNotExist);
+        } else {
+            get_unlocked_tranches(&e, &stream.unwrap_optimized())
+        }
+    }
+
+    /// Returns the status of the stream.
+    fn status(e: Env, stream_id: u32) -> Option<Status> {
+        let stream_resp = storage::get_stream(&e, &stream_id);
+
+        let stream = stream_resp?;
+
+        let current_ledger_time = e.ledger().timestamp();
+
+        if stream.is_depleted {
+            return Some(Status::Depleted);
+        }
+
+        if stream.is_cancelled {
+            return Some(Status::Cancelled);
+        }
+
+        if stream.is_paused {
+            return Some(Status::Paused);
+        }
+
+        if current_ledger_time < stream.start_time {
+            return Some(Status::Pending);
+        }
+
+        if current_ledger_time < stream.cliff_time {
+            return Some(Status::Cliff);
+        }
+
+        let streamed_amount = get_streamed_amount(&e, &stream);
+
+        if streamed_amount >= stream.deposit {
+            return Some(Status::Completed);
+        }
+
+        Some(Status::Active)
+    }
+
+    fn create_range(
+        e: Env,
+        sender: Address,
+        recipient: Address,
+        amount: i128,
+        token_address: Address,
+        start_time: u64,
+        stop_time: u64,
+        cancellable: bool,
+        cliff_time: u64,
+        cliff_amount: i128,
+        transferable: bool,
+    ) -> u32 {
+        sender.require_auth();
+        storage::extend_instance(&e);
+        require_not_paused(&e);
+
+        let params = RangeParams {
+            recipient,
+            amount,
+            start_time,
+            stop_time,
+            cliff_time,
+            cliff_amount,
+            cancellable,
+            transferable,
+        };
+        if let Err(error) = check_range_params(&e, &params) {
+            panic_with_error!(&e, error);
+        }
+
+        create_stream(&e, new_range_stream(sender, token_address, params))
+    }
+
+    fn create_range_batch(
+        e: Env,
+        sender: Address,
+        token_address: Address,
+        params: Vec<RangeParams>,
+    ) -> Vec<u32> {
+        sender.require_auth();
+        storage::extend_instance(&e);
+        require_not_paused(&e);
+
+        if params.is_empty() || params.len() > MAX_CREATE_BATCH {
+            panic_with_error!(&e, StreamError::InvalidBatch);
+        }
+
+        let mut streams: Vec<DirectStreamData> = Vec::new(&e);
+        let mut total_amount: i128 = 0;
+        let mut total_fee: i128 = 0;
+        for (index, stream_params) in params.iter().enumerate() {
+            if let Err(error) = check_range_params(&e, &stream_params) {
+                panic_with_error!(&e, get_batch_error(index as u32, error));
+            }
+
+            let mut stream =
+                new_range_stream(sender.clone(), token_address.clone(), stream_params);
+            stream.fee = get_fee(&e, &token_address, stream.deposit);
+            total_amount = total_amount
+                .checked_add(stream.deposit + stream.fee)
+                .unwrap_or_else(|| panic_with_error!(&e, StreamError::OverflowError));
+            total_fee += stream.fee;
+            streams.push_back(stream);
+        }
+
+        // pull the deposits and fees for every stream in a single transfer
+        transfer(
+            &e,
+            &sender,
+            &e.current_contract_address(),
+            &total_amount,
+            &token_address,
+        );
+        accrue_fee(&e, &token_address, total_fee);
+
+        let mut stream_ids = Vec::new(&e);
+        for stream in streams.into_iter() {
+            stream_ids.push_back(store_new_stream(&e, stream));
+        }
+        stream_ids
+    }
+
+    fn create_with_segments(
+        e: Env,
+        sender: Address,
+        recipient: Address,
+        amount: i128,
+        token_address: Address,
+        start_time: u64,
+        segments: Vec<Segment>,
+        cancellable: bool,
+    ) -> u32 {
+        sender.require_auth();
+        storage::extend_instance(&e);
+        require_not_paused(&e);
+        require_positive_amount(&e, amount);
+        let stop_time = require_valid_segments(&e, amount, start_time, &segments);
+
+        let stream_id = create_stream(
+            &e,
+            DirectStreamData {
+                id: 0,
+                kind: StreamKind::Segments,
+                sender,
+                recipient,
+                start_time,
+                stop_time,
+                cliff_time: start_time,
+                deposit: amount,
+                cliff_amount: 0,
+                fee: 0,
+                is_cancelled: false,
+                is_cancellable: cancellable,
+                is_transferable: false,
+                is_depleted: false,
+                is_paused: false,
+                paused_time: 0,
+                refunded: 0,
+                withdrawn: 0,
+                token_address,
+            },
+        );
+        storage::set_segments(&e, &stream_id, &segments);
+        stream_id
+    }
+
+    fn create_with_tranches(
+        e: Env,
+        sender: Address,
+        recipient: Address,
+        amount: i128,
+        token_address: Address,
+        start_time: u64,
+        tranches: Vec<Tranche>,
+        cancellable: bool,
+    ) -> u32 {
+        sender.require_auth();
+        storage::extend_instance(&e);
+        require_not_paused(&e);
+        require_positive_amount(&e, amount);
+        let stop_time = require_valid_tranches(&e, amount, start_time, &tranches);
+
+        let stream_id = create_stream(
+            &e,
+            DirectStreamData {
+                id: 0,
+                kind: StreamKind::Tranches,
+                sender,
+                recipient,
+                start_time,
+                stop_time,
+                cliff_time: start_time,
+                deposit: amount,
+                cliff_amount: 0,
+                fee: 0,
+                is_cancelled: false,
+                is_cancellable: cancellable,
+                is_transferable: false,
+                is_depleted: false,
+                is_paused: false,
+                paused_time: 0,
+                refunded: 0,
+                withdrawn: 0,
+                token_address,
+            },
+        );
+        storage::set_tranches(&e, &stream_id, &tranches);
+        stream_id
+    }
+
+    fn withdraw(e: Env, caller: Address, to: Address, stream_id: u32, amount: i128) {
+        caller.require_auth();
+        storage::extend_instance(&e);
+        require_positive_amount(&e, amount);
+
+        let stream_resp = storage::get_stream(&e, &stream_id);
+
+        if stream_resp.is_none() {
+            panic_with_error!(&e, StreamError::StreamDoesNotExist);
+        }
+
+        let mut stream = stream_resp.unwrap_optimized();
+
+        require_withdrawer(&e, &stream, &caller);
+        require_valid_destination(&e, &stream, &caller, &to);
+
+        let withdrawable_amount = get_withdrawable_amount(&e, &stream);
+        if amount > withdrawable_amount {
+            panic_with_error!(&e, StreamError::ExceedsStreamedAmount);
+        };
+
+        withdraw_from_stream(&e, &mut stream, &to, amount);
+    }
+
+    fn withdraw_max(e: Env, caller: Address, stream_id: u32, to: Address) -> i128 {
+        caller.require_auth();
+        storage::extend_instance(&e);
+
+        let stream_resp = storage::get_stream(&e, &stream_id);
+
+        if stream_resp.is_none() {
+            panic_with_error!(&e, StreamError::StreamDoesNotExist);
+        }
+
+        let mut stream = stream_resp.unwrap_optimized();
+
+        require_withdrawer(&e, &stream, &caller);
+        require_valid_destination(&e, &stream, &caller, &to);
+
+        let amount = get_withdrawable_amount(&e, &stream);
+        if amount > 0 {
+            withdraw_from_stream(&e, &mut stream, &to, amount);
+        }
+        amount
+    }
+
+    fn withdraw_multiple(
+        e: Env,
+        caller: Address,
+        stream_ids: Vec<u32>,
+        to: Address,
+    ) -> Vec<i128> {
+        caller.require_auth();
+        storage::extend_instance(&e);
+
+        if stream_ids.is_empty() || stream_ids.len() > MAX_WITHDRAW_BATCH {
+            panic_with_error!(&e, StreamError::InvalidBatch);
+        }
+
+        // withdraw from each stream, totalling the amount to transfer for each token
+        let mut amounts = Vec::new(&e);
+        let mut token_totals: Map<Address, i128> = Map::new(&e);
+        let mut stream_writes = 0;
+        for stream_id in stream_ids.iter() {
+            let mut stream = storage::get_stream(&e, &stream_id)
+                .unwrap_or_else(|| panic_with_error!(&e, StreamError::StreamDoesNotExist));
+
+            require_withdrawer(&e, &stream, &caller);
+            require_valid_destination(&e, &stream, &caller, &to);
+
+            let amount = get_withdrawable_amount(&e, &stream);
+            if amount > 0 {
+                record_withdrawal(&e, &mut stream, amount);
+                stream_writes += 1;
+                StreamEvents::stream_withdrawn(&e, to.clone(), stream.id, amount);
+                let token_total = token_totals.get(stream.token_address.clone()).unwrap_or(0);
+                token_totals.set(stream.token_address, token_total + amount);
+            }
+            amounts.push_back(amount);
+        }
+
+        // each token transferred writes the balances of the contract and the destination
+        require_batch_writes(&e, stream_writes + 2 * token_totals.len());
+        for (token_address, amount) in token_totals.iter() {
+            transfer(
+                &e,
+                &e.current_contract_address(),
+                &to,
+                &amount,
+                &token_address,
+            );
+        }
+        amounts
+    }
+
+    /// Cancels the stream and refunds the unstreamed tokens to the sender.
+    /// The amount streamed up to the cancellation remains withdrawable by the recipient.
+    /// Throws if the id does not point to a valid stream.
+    /// Throws if the stream is not cancellable, or is already cancelled or depleted.
+    /// Throws if the caller is not the sender or the recipient of the stream.
+    /// Throws if there is a token transfer failure.
+    /// @param stream_id The id of the stream to cancel.
+    fn cancel(e: Env, caller: Address, stream_id: u32) {
+        caller.require_auth();
+        storage::extend_instance(&e);
+        let stream = storage::get_stream(&e, &stream_id);
+
+        if stream.is_none() {
+            panic_with_error!(&e, StreamError::StreamDoesNotExist);
+        }
+
+        let mut stream = stream.unwrap_optimized();
+        require_sender_or_recipient(&e, &stream, &caller);
+
+        if !stream.is_cancellable {
+            panic_with_error!(&e, StreamError::NotCancellable);
+        }
+        if stream.is_cancelled || stream.is_depleted {
+            panic_with_error!(&e, StreamError::StreamSettled);
+        }
+
+        let sender_balance = settle_cancellation(&e, &mut stream);
+
+        if sender_balance > 0 {
+            transfer(
+                &e,
+                &e.current_contract_address(),
+                &stream.sender,
+                &sender_balance,
+                &stream.token_address,
+            );
+        }
+
+        StreamEvents::stream_cancelled(&e, stream_id);
+    }
+
+    fn cancel_multiple(
+        e: Env,
+        caller: Address,
+        stream_ids: Vec<u32>,
+        skip_settled: bool,
+    ) -> Vec<i128> {
+        caller.require_auth();
+        storage::extend_instance(&e);
+
+        if stream_ids.is_empty() || stream_ids.len() > MAX_CANCEL_BATCH {
+            panic_with_error!(&e, StreamError::InvalidBatch);
+        }
+
+        // cancel each stream, totalling the refund owed to each sender for each token
+        let mut refunds = Vec::new(&e);
+        let mut refund_totals: Map<(Address, Address), i128> = Map::new(&e);
+        let mut stream_writes = 0;
+        for stream_id in stream_ids.iter() {
+            let mut stream = storage::get_stream(&e, &stream_id)
+                .unwrap_or_else(|| panic_with_error!(&e, StreamError::StreamDoesNotExist));
+
+            require_sender_or_recipient(&e, &stream, &caller);
+
+            if !stream.is_cancellable {
+                panic_with_error!(&e, StreamError::NotCancellable);
+            }
+            if stream.is_cancelled || stream.is_depleted {
+                if skip_settled {
+                    refunds.push_back(0);
+                    continue;
+                }
+                panic_with_error!(&e, StreamError::StreamSettled);
+            }
+
+            let sender_balance = settle_cancellation(&e, &mut stream);
+            stream_writes += 1;
+            if sender_balance > 0 {
+                let key = (stream.sender, stream.token_address);
+                let refund_total = refund_totals.get(key.clone()).unwrap_or(0);
+                refund_totals.set(key, refund_total + sender_balance);
+            }
+            refunds.push_back(sender_balance);
+
+            StreamEvents::stream_cancelled(&e, stream_id);
+        }
+
+        // each refund writes the sender's balance, and each token refunded the contract's
+        let mut refunded_tokens: Vec<Address> = Vec::new(&e);
+        for (_, token_address) in refund_totals.keys().iter() {
+            if !refunded_tokens.contains(&token_address) {
+                refunded_tokens.push_back(token_address);
+            }
+        }
+        require_batch_writes(
+            &e,
+            stream_writes + refund_totals.len() + refunded_tokens.len(),
+        );
+        for ((sender, token_address), amount) in refund_totals.iter() {
+            transfer(
+                &e,
+                &e.current_contract_address(),
+                &sender,
+                &amount,
+                &token_address,
+            );
+        }
+        refunds
+    }
+
+    fn renounce(e: Env, stream_id: u32) {
+        let stream = storage::get_stream(&e, &stream_id);
+
+        if stream.is_none() {
+            panic_with_error!(&e, StreamError::StreamDoesNotExist);
+        }
+
+        let mut stream = stream.unwrap_optimized();
+        stream.sender.require_auth();
+
+        if !stream.is_cancellable || stream.is_cancelled {
+            panic_with_error!(&e, StreamError::NotCancellable);
+        }
+
+        stream.is_cancellable = false;
+
+        storage::set_stream(&e, &stream_id, &stream);
+
+        StreamEvents::stream_renounced(&e, stream_id);
+    }
+
+    fn pause(e: Env, stream_id: u32) {
+        storage::extend_instance(&e);
+        let stream = storage::get_stream(&e, &stream_id);
+
+        if stream.is_none() {
+            panic_with_error!(&e, StreamError::StreamDoesNotExist);
+        }
+
+        let mut stream = stream.unwrap_optimized();
+        stream.sender.require_auth();
+
+        // pausing withholds streamed funds, which the sender gave up by renouncing
+        if !stream.is_cancellable {
+            panic_with_error!(&e, StreamError::NotCancellable);
+        }
+        if stream.is_paused {
+            panic_with_error!(&e, StreamError::StreamPaused);
+        }
+        // there is nothing left to pause once the full deposit is streamed
+        if stream.is_cancelled
+            || stream.is_depleted
+            || get_streamed_amount(&e, &stream) == stream.deposit
+        {
+            panic_with_error!(&e, StreamError::StreamSettled);
+        }
+
+        stream.is_paused = true;
+        stream.paused_time = e.ledger().timestamp();
+
+        storage::set_stream(&e, &stream_id, &stream);
+
+        StreamEvents::stream_paused(&e, stream_id);
+    }
+
+    fn resume(e: Env, stream_id: u32) {
+        storage::extend_instance(&e);
+        require_not_paused(&e);
+        let stream = storage::get_stream(&e, &stream_id);
+
+        if stream.is_none() {
+            panic_with_error!(&e, StreamError::StreamDoesNotExist);
+        }
+
+        let mut stream = stream.unwrap_optimized();
+        stream.sender.require_auth();
+
+        if !stream.is_paused || stream.is_cancelled {
+            panic_with_error!(&e, StreamError::StreamNotPaused);
+        }
+
+        // the remaining schedule picks up where it was paused
+        let paused_duration = e.ledger().timestamp() - stream.paused_time;
+        shift_schedule(&e, &mut stream, paused_duration);
+        stream.is_paused = false;
+        stream.paused_time = 0;
+
+        storage::set_stream(&e, &stream_id, &stream);
+
+        StreamEvents::stream_resumed(&e, stream_id, paused_duration);
+    }
+
+    fn top_up(e: Env, stream_id: u32, amount: i128, new_stop_time: u64) {
+        storage::extend_instance(&e);
+        require_not_paused(&e);
+        require_positive_amount(&e, amount);
+        let stream = storage::get_stream(&e, &stream_id);
+
+        if stream.is_none() {
+            panic_with_error!(&e, StreamError::StreamDoesNotExist);
+        }
+
+        let mut stream = stream.unwrap_optimized();
+        stream.sender.require_auth();
+
+        if stream.kind != StreamKind::Range {
+            panic_with_error!(&e, StreamError::InvalidStreamKind);
+        }
+        if stream.is_paused {
+            panic_with_error!(&e, StreamError::StreamPaused);
+        }
+        let current_ledger_time = e.ledger().timestamp();
+        if stream.is_cancelled || stream.is_depleted || current_ledger_time >= stream.stop_time {
+            panic_with_error!(&e, StreamError::StreamSettled);
+        }
+        if new_stop_time < stream.stop_time {
+            panic_with_error!(&e, StreamError::InvalidStopTime);
+        }
+        require_unlock_rate_kept(&e, &stream, amount, new_stop_time);
+
+        // once past the cliff, restart the schedule from now with the amount accrued so
+        // far unlocked up front, so the recipient's accrued amount never decreases
+        if current_ledger_time >= stream.cliff_time {
+            stream.cliff_amount = get_streamed_amount(&e, &stream);
+            stream.start_time = current_ledger_time;
+            stream.cliff_time = current_ledger_time;
+        }
+        stream.deposit = stream
+            .deposit
+            .checked_add(amount)
+            .unwrap_or_else(|| panic_with_error!(&e, StreamError::OverflowError));
+        stream.stop_time = new_stop_time;
+
+        let fee = get_fee(&e, &stream.token_address, amount);
+        stream.fee += fee;
+
+        transfer(
+            &e,
+            &stream.sender,
+            &e.current_contract_address(),
+            &(amount + fee),
+            &stream.token_address,
+        );
+
+        accrue_fee(&e, &stream.token_address, fee);
+        storage::set_stream(&e, &stream_id, &stream);
+
+        StreamEvents::stream_topped_up(&e, stream_id, amount, fee, new_stop_time);
+    }
+
+    fn owner_of(e: Env, stream_id: u32) -> Address {
+        storage::get_stream(&e, &stream_id)
+            .unwrap_or_else(|| panic_with_error!(&e, StreamError::StreamDoesNotExist))
+            .recipient
+    }
+
+    fn get_approved(e: Env, stream_id: u32) -> Option<Address> {
+        storage::get_approval(&e, &stream_id)
+    }
+
+    fn is_approved_for_all(e: Env, owner: Address, operator: Address) -> bool {
+        storage::get_operator(&e, &owner, &operator)
+    }
+
+    fn approve(e: Env, owner: Address, approved: Option<Address>, stream_id: u32) {
+        owner.require_auth();
+        storage::extend_instance(&e);
+
+        let stream = storage::get_stream(&e, &stream_id)
+            .unwrap_or_else(|| panic_with_error!(&e, StreamError::StreamDoesNotExist));
+        require_recipient(&e, &stream, &owner);
+        if !stream.is_transferable {
+            panic_with_error!(&e, StreamError::NotTransferable);
+        }
+
+        storage::set_approval(&e, &stream_id, &approved);
+
+        StreamEvents::approve(&e, owner, stream_id, approved);
+    }
+
+    fn approve_for_all(e: Env, owner: Address, operator: Address, approved: bool) {
+        owner.require_auth();
+        storage::extend_instance(&e);
+
+        storage::set_operator(&e, &owner, &operator, approved);
+
+        StreamEvents::approve_for_all(&e, owner, operator, approved);
+    }
+
+    fn transfer(e: Env, from: Address, to: Address, stream_id: u32) {
+        from.require_auth();
+        storage::extend_instance(&e);
+
+        let mut stream = storage::get_stream(&e, &stream_id)
+            .unwrap_or_else(|| panic_with_error!(&e, StreamError::StreamDoesNotExist));
+
+        transfer_stream(&e, &mut stream, &from, &to);
+    }
+
+    fn transfer_from(e: Env, spender: Address, from: Address, to: Address, stream_id: u32) {
+        spender.require_auth();
+        storage::extend_instance(&e);
+
+        let mut stream = storage::get_stream(&e, &stream_id)
+            .unwrap_or_else(|| panic_with_error!(&e, StreamError::StreamDoesNotExist));
+
+        let is_approved = storage::get_approval(&e, &stream_id) == Some(spender.clone())
+            || storage::get_operator(&e, &from, &spender);
+        if !is_approved {
+            panic_with_error!(&e, StreamError::NotApproved);
+        }
+
+        transfer_stream(&e, &mut stream, &from, &to);
+    }
+
+    fn pending_sender(e: Env, stream_id: u32) -> Option<Address> {
+        storage::get_pending_sender(&e, &stream_id)
+    }
+
+    fn propose_sender_transfer(e: Env, stream_id: u32, new_sender: Address) {
+        storage::extend_instance(&e);
+
+        let stream = storage::get_stream(&e, &stream_id)
+            .unwrap_or_else(|| panic_with_error!(&e, StreamError::StreamDoesNotExist));
+        stream.sender.require_auth();
+
+        propose_sender(&e, &stream, &new_sender);
+    }
+
+    fn accept_sender_transfer(e: Env, new_sender: Address, stream_id: u32) {
+        new_sender.require_auth();
+        storage::extend_instance(&e);
+
+        let mut stream = storage::get_stream(&e, &stream_id)
+            .unwrap_or_else(|| panic_with_error!(&e, StreamError::StreamDoesNotExist));
+
+        accept_sender(&e, &mut stream, &new_sender);
+    }
+
+    fn propose_sender_transfer_multiple(
+        e: Env,
+        sender: Address,
+        stream_ids: Vec<u32>,
+        new_sender: Address,
+    ) {
+        sender.require_auth();
+        storage::extend_instance(&e);
+
+        if stream_ids.is_empty() || stream_ids.len() > MAX_SENDER_TRANSFER_BATCH {
+            panic_with_error!(&e, StreamError::InvalidBatch);
+        }
+
+        for stream_id in stream_ids.iter() {
+            let stream = storage::get_stream(&e, &stream_id)
+                .unwrap_or_else(|| panic_with_error!(&e, StreamError::StreamDoesNotExist));
+            if stream.sender != sender {
+                panic_with_error!(&e, StreamError::NotSender);
+            }
+
+            propose_sender(&e, &stream, &new_sender);
+        }
+    }
+
+    fn accept_sender_transfer_multiple(e: Env, new_sender: Address, stream_ids: Vec<u32>) {
+        new_sender.require_auth();
+        storage::extend_instance(&e);
+
+        if stream_ids.is_empty() || stream_ids.len() > MAX_SENDER_TRANSFER_BATCH {
+            panic_with_error!(&e, StreamError::InvalidBatch);
+        }
+
+        for stream_id in stream_ids.iter() {
+            let mut stream = storage::get_stream(&e, &stream_id)
+                .unwrap_or_else(|| panic_with_error!(&e, StreamError::StreamDoesNotExist));
+
+            accept_sender(&e, &mut stream, &new_sender);
+        }
+    }
+
+    fn is_operator(e: Env, recipient: Address, operator: Address) -> bool {
+        is_active_operator(&e, &recipient, &operator)
+    }
+
+    fn approve_operator(e: Env, recipient: Address, operator: Address, expiry: u64) {
+        recipient.require_auth();
+        storage::extend_instance(&e);
+
+        if expiry <= e.ledger().timestamp() {
+            panic_with_error!(&e, StreamError::InvalidExpiry);
+        }
+
+        storage::set_withdraw_operator(&e, &recipient, &operator, &Some(expiry));
+
+        StreamEvents::operator_approved(&e, recipient, operator, expiry);
+    }
+
+    fn revoke_operator(e: Env, recipient: Address, operator: Address) {
+        recipient.require_auth();
+        storage::extend_instance(&e);
+
+        storage::set_withdraw_operator(&e, &recipient, &operator, &None);
+
+        StreamEvents::operator_revoked(&e, recipient, operator);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use soroban_sdk::testutils::{Address as _, EnvTestConfig};
+
+    use super::*;
+
+    const ITERATIONS: u32 = 10_000;
+
+    /// Small deterministic xorshift generator so the property tests are reproducible
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn range(&mut self, min: u64, max: u64) -> u64 {
+            min + self.next() % (max - min + 1)
+        }
+
+        fn range_i128(&mut self, min: i128, max: i128) -> i128 {
+            let wide = ((self.next() as u128) << 64) | self.next() as u128;
+            min + (wide % ((max - min) as u128 + 1)) as i128
+        }
+    }
+
+    fn new_env() -> Env {
+        let e = Env::new_with_config(EnvTestConfig {
+            capture_snapshot_at_drop: false,
+        });
+        e.budget().reset_unlimited();
+        e
+    }
+
+    /// Generate a range stream mixing dust, typical and near `i128::MAX` deposits
+    fn random_range_stream(rng: &mut Rng, user: &Address) -> DirectStreamData {
+        let amount = match rng.range(0, 3) {
+            0 => rng.range_i128(1, 1_000),
+            1 => rng.range_i128(1_000, 1_000_000_000_000),
+            2 => rng.range_i128(i128::MAX / 2, i128::MAX / 2 + 1_000_000),
+            _ => rng.range_i128(i128::MAX - 1_000_000, i128::MAX),
+        };
+        let cliff_amount = match rng.range(0, 3) {
+            0 => 0,
+            1 => amount,
+            _ => rng.range_i128(0, amount),
+        };
+        let start_time = rng.range(0, 1_000_000);
+        let stop_time = start_time + rng.range(1, 1_000_000_000);
+        let cliff_time = rng.range(start_time, stop_time - 1);
+        new_range_stream(
+            user.clone(),
+            user.clone(),
+            RangeParams {
+                recipient: user.clone(),
+                amount,
+                start_time,
+                stop_time,
+                cliff_time,
+                cliff_amount,
+                cancellable: true,
+                transferable: false,
+            },
+        )
+    }
+
+    #[test]
+    fn test_range_streamed_amount_properties() {
+        let e = new_env();
+        let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+        let user = Address::generate(&e);
+
+        for _ in 0..ITERATIONS {
+            let stream = random_range_stream(&mut rng, &user);
+            let total_time = stream.stop_time - stream.start_time;
+
+            // sample the edges of the schedule along with random times, in order
+            let mut times = std::vec![
+                stream.start_time,
+                stream.cliff_time,
+                stream.stop_time - 1,
+                stream.stop_time,
+                stream.stop_time + rng.range(1, 1_000_000),
+            ];
+            if stream.cliff_time > stream.start_time {
+                times.push(stream.cliff_time - 1);
+            }
+            for _ in 0..4 {
+                times.push(rng.range(stream.start_time, stream.stop_time));
+            }
+            times.sort_unstable();
+
+            let mut last_streamed = 0;
+            for time in times {
+                let streamed = get_range_streamed_amount(&e, &stream, time);
+
+                assert!(streamed >= last_streamed, "streamed amount decreased");
+                assert!(streamed >= 0 && streamed <= stream.deposit);
+                if time < stream.cliff_time {
+                    assert_eq!(streamed, 0);
+                } else if time >= stream.stop_time {
+                    assert_eq!(streamed, stream.deposit);
+                } else {
+                    assert!(streamed >= stream.cliff_amount);
+                }
+                // with one second left, anything not unlocked at the cliff is still streaming
+                if time == stream.stop_time - 1
+                    && stream.cliff_time < time
+                    && stream.deposit > stream.cliff_amount
+                    && total_time > 1
+                {
+                    assert!(streamed < stream.deposit);
+                }
+
+                last_streamed = streamed;
+            }
+        }
+    }
+
+    #[test]
+    fn test_mul_div_floor_properties() {
+        let e = new_env();
+        let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+
+        for _ in 0..ITERATIONS {
+            let x = match rng.range(0, 2) {
+                0 => rng.range_i128(0, 1_000_000),
+                1 => rng.range_i128(0, i128::MAX),
+                _ => rng.range_i128(i128::MAX - 1_000_000, i128::MAX),
+            };
+            let z = rng.range_i128(1, u64::MAX as i128);
+            let y = match rng.range(0, 2) {
+                0 => 0,
+                1 => z - 1,
+                _ => rng.range_i128(0, z),
+            };
+
+            let result = mul_div_floor(&e, x, y, z);
+            assert!(result >= 0 && result <= x);
+            if y == z {
+                assert_eq!(result, x);
+            }
+            if y < z {
+                // one more unit of time never streams less
+                assert!(mul_div_floor(&e, x, y + 1, z) >= result);
+            }
+        }
     }
 }
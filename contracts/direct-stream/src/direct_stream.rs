@@ -1,6 +1,8 @@
-use soroban_sdk::{contractclient, Address, Env};
+use soroban_sdk::{contractclient, Address, BytesN, Env, Vec};
 
-use crate::types::{StreamSettings, DirectStreamData, Status};
+use crate::types::{
+    DirectStreamData, FeeModel, RangeParams, Role, Segment, Status, StreamSettings, Tranche,
+};
 
 #[contractclient(name = "DirectStreamClient")]
 pub trait DirectStream {
@@ -13,6 +15,153 @@ pub trait DirectStream {
     /// Get the current settings of the stream
     fn settings(e: Env) -> StreamSettings;
 
+    /// Get the address proposed to take over as admin, if any
+    fn pending_admin(e: Env) -> Option<Address>;
+
+    /// (Admin only) Propose `new_admin` to take over as admin, replacing any previous
+    /// proposal. The admin is unchanged until `new_admin` accepts.
+    ///
+    /// ### Arguments
+    /// * `new_admin` - The proposed admin
+    fn propose_admin(e: Env, new_admin: Address);
+
+    /// (Proposed admin only) Accept the proposal to take over as admin
+    ///
+    /// ### Panics
+    /// If no admin has been proposed
+    fn accept_admin(e: Env);
+
+    /// Check if `account` holds `role`, either by being granted it or by being the admin,
+    /// who holds every role implicitly
+    ///
+    /// ### Arguments
+    /// * `role` - The role
+    /// * `account` - The account
+    fn has_role(e: Env, role: Role, account: Address) -> bool;
+
+    /// (Admin only) Grant `role` to `account`
+    ///
+    /// ### Arguments
+    /// * `role` - The role
+    /// * `account` - The account
+    fn grant_role(e: Env, role: Role, account: Address);
+
+    /// (Admin only) Revoke `role` from `account`
+    ///
+    /// ### Arguments
+    /// * `role` - The role
+    /// * `account` - The account
+    fn revoke_role(e: Env, role: Role, account: Address);
+
+    /// (Admin or upgrader only) Upgrade the contract's wasm
+    ///
+    /// ### Arguments
+    /// * `caller` - The admin or an upgrader
+    /// * `wasm_hash` - The hash of the uploaded wasm to upgrade to
+    ///
+    /// ### Panics
+    /// If the caller is not the admin or an upgrader
+    fn upgrade(e: Env, caller: Address, wasm_hash: BytesN<32>);
+
+    /// Check if the contract is paused
+    fn paused(e: Env) -> bool;
+
+    /// Check if withdrawals by anyone but the recipient are paused
+    fn withdrawals_paused(e: Env) -> bool;
+
+    /// (Admin or pauser only) Pause or unpause the contract
+    ///
+    /// While paused, streams can't be created, topped up or resumed. If `pause_withdrawals`
+    /// is set, only recipients can withdraw as well. Recipients can always withdraw, and
+    /// senders can still cancel and pause their streams. Unpausing the contract also
+    /// unpauses withdrawals. The pause only covers this contract, not flow streams.
+    ///
+    /// ### Arguments
+    /// * `caller` - The admin or a pauser
+    /// * `paused` - If the contract is paused
+    /// * `pause_withdrawals` - If withdrawals by anyone but the recipient are paused,
+    ///   ignored when unpausing
+    ///
+    /// ### Panics
+    /// If the caller is not the admin or a pauser
+    fn set_paused(e: Env, caller: Address, paused: bool, pause_withdrawals: bool);
+
+    /// (Admin or fee manager only) Set the fee charged when a stream of `token_address`
+    /// is created
+    ///
+    /// ### Arguments
+    /// * `caller` - The admin or a fee manager
+    /// * `token_address` - The address of the token
+    /// * `fee` - The fee model used to calculate the fee
+    ///
+    /// ### Panics
+    /// If the caller is not the admin or a fee manager, the fee model contains a negative
+    /// amount or its basis points exceed the protocol maximum
+    fn set_asset_fee(e: Env, caller: Address, token_address: Address, fee: FeeModel);
+
+    /// (Admin or fee manager only) Remove the fee set for `token_address`
+    ///
+    /// ### Arguments
+    /// * `caller` - The admin or a fee manager
+    /// * `token_address` - The address of the token
+    ///
+    /// ### Panics
+    /// If the caller is not the admin or a fee manager, or no fee is set for the token
+    fn remove_asset_fee(e: Env, caller: Address, token_address: Address);
+
+    /// Get the fee set for `token_address`, if any
+    ///
+    /// ### Arguments
+    /// * `token_address` - The address of the token
+    fn asset_fee(e: Env, token_address: Address) -> Option<FeeModel>;
+
+    /// (Admin or fee manager only) Migrate the fee for `token_address` from temporary
+    /// storage, where previous versions of the contract stored it, to persistent storage
+    ///
+    /// Fees are also migrated the first time they are read, so this is only needed to
+    /// move a fee before it expires from temporary storage.
+    ///
+    /// ### Arguments
+    /// * `caller` - The admin or a fee manager
+    /// * `token_address` - The address of the token
+    ///
+    /// ### Panics
+    /// If the caller is not the admin or a fee manager, or no unexpired fee exists in
+    /// temporary storage
+    fn migrate_asset_fee(e: Env, caller: Address, token_address: Address);
+
+    /// Quote the fee charged to create a stream of `amount` of `token_address`
+    ///
+    /// The fee set for the token is used if one exists, otherwise the `base_fee`
+    /// from the settings applies. The fee is capped at 10% of `amount`. The sender is
+    /// charged `amount + fee`.
+    ///
+    /// ### Arguments
+    /// * `token_address` - The address of the token
+    /// * `amount` - The amount to be streamed
+    ///
+    /// ### Panics
+    /// If the amount is zero or negative
+    fn quote_fee(e: Env, token_address: Address, amount: i128) -> i128;
+
+    /// (Admin only) Claim protocol fees accrued for `token_address`
+    ///
+    /// ### Arguments
+    /// * `token_address` - The address of the token
+    /// * `to` - The address receiving the fees
+    /// * `amount` - The amount of fees to claim
+    ///
+    /// ### Panics
+    /// If the caller is not the admin, the amount is zero or negative, or
+    /// the amount exceeds the accrued fees
+    fn claim_fees(e: Env, token_address: Address, to: Address, amount: i128);
+
+    /// Get the protocol fees accrued and not yet claimed for `token_address`
+    ///
+    /// ### Arguments
+    /// * `token_address` - The address of the token
+    fn accrued_fees(e: Env, token_address: Address) -> i128;
+
     /// Create a new proposal
     ///
     /// Returns the id of the new proposal
@@ -29,8 +178,54 @@ pub trait DirectStream {
 
     fn streamed_amount(e: Env, stream_id: u32) -> i128;
 
+    /// Get the amount of tokens streamed to the recipient that has not been withdrawn yet
+    ///
+    /// ### Arguments
+    /// * `stream_id` - The id of the stream
+    ///
+    /// ### Panics
+    /// If the stream does not exist
+    fn withdrawable_amount(e: Env, stream_id: u32) -> i128;
+
     fn status(e: Env, stream_id: u32) -> Option<Status>; 
 
+    /// Get the number of tranches of a tranched stream unlocked so far
+    ///
+    /// Always 0 for streams that are not tranched. A cancelled stream keeps the tranches
+    /// that unlocked before it was cancelled, and a paused stream the ones that unlocked
+    /// before it was paused.
+    ///
+    /// ### Arguments
+    /// * `stream_id` - The id of the stream
+    ///
+    /// ### Panics
+    /// If the stream does not exist
+    fn unlocked_tranches(e: Env, stream_id: u32) -> u32;
+
+    /// Create a stream of `amount` of `token_address` from the sender to the recipient
+    ///
+    /// Nothing is streamed before `cliff_time`. At `cliff_time`, `cliff_amount` is unlocked
+    /// along with the amount accrued since `start_time`, and the remainder of the deposit
+    /// streams linearly until `stop_time`.
+    ///
+    /// Returns the id of the new stream
+    ///
+    /// ### Arguments
+    /// * `sender` - The address funding the stream
+    /// * `recipient` - The address receiving the stream
+    /// * `amount` - The amount to stream, excluding the protocol fee
+    /// * `token_address` - The address of the token to stream
+    /// * `start_time` - The time the stream starts
+    /// * `stop_time` - The time the stream ends
+    /// * `cancellable` - If the stream can be cancelled
+    /// * `cliff_time` - The time before which nothing can be withdrawn
+    /// * `cliff_amount` - The amount unlocked at `cliff_time`, or zero for no unlock
+    /// * `transferable` - If the recipient can transfer the right to withdraw from the stream
+    ///
+    /// ### Panics
+    /// If the contract is paused, the amount is zero or negative, the times are not
+    /// ordered as `now <= start_time <= cliff_time < stop_time`, or the cliff amount is
+    /// negative or exceeds the amount
     fn create_range(
         e: Env,
         sender: Address,
@@ -41,15 +236,377 @@ pub trait DirectStream {
         stop_time: u64,
         cancellable: bool,
         cliff_time: u64,
+        cliff_amount: i128,
+        transferable: bool,
     ) -> u32;
 
-    fn withdraw(
+    /// Create a batch of range streams of `token_address` from the sender, pulling the
+    /// deposits and fees for every stream in a single transfer
+    ///
+    /// Returns the ids of the new streams, in the order of `params`
+    ///
+    /// ### Arguments
+    /// * `sender` - The address funding the streams
+    /// * `token_address` - The address of the token to stream
+    /// * `params` - The recipient, amount and schedule of each stream
+    ///
+    /// ### Panics
+    /// If the contract is paused, the batch is empty or has more than 20 streams, or any
+    /// stream's params are invalid as in `create_range`, in which case no streams are
+    /// created. Invalid params fail with the error code `(index + 1) * 1000 + error`, where
+    /// `index` is the position of the params in the batch and `error` is the code
+    /// `create_range` fails with.
+    fn create_range_batch(
         e: Env,
-        caller: Address,
+        sender: Address,
+        token_address: Address,
+        params: Vec<RangeParams>,
+    ) -> Vec<u32>;
+
+    /// Create a stream of `amount` of `token_address` that streams through `segments`
+    ///
+    /// Each segment streams its amount between the end of the previous segment, or
+    /// `start_time` for the first segment, and its own end time, along its exponent's curve.
+    /// The stream is not transferable.
+    ///
+    /// Returns the id of the new stream
+    ///
+    /// ### Arguments
+    /// * `sender` - The address funding the stream
+    /// * `recipient` - The address receiving the stream
+    /// * `amount` - The amount to stream, excluding the protocol fee
+    /// * `token_address` - The address of the token to stream
+    /// * `start_time` - The time the stream starts
+    /// * `segments` - The segments of the stream, ordered by end time
+    /// * `cancellable` - If the stream can be cancelled
+    ///
+    /// ### Panics
+    /// If the contract is paused, the amount is zero or negative, the start time is in the
+    /// past, the segments are empty, not ordered after `start_time` or do not sum to the
+    /// amount
+    fn create_with_segments(
+        e: Env,
+        sender: Address,
         recipient: Address,
-        stream_id: u32,
         amount: i128,
-    );
+        token_address: Address,
+        start_time: u64,
+        segments: Vec<Segment>,
+        cancellable: bool,
+    ) -> u32;
+
+    /// Create a stream of `amount` of `token_address` that unlocks in `tranches`
+    ///
+    /// Each tranche unlocks its full amount at its unlock time, and nothing is
+    /// streamed in between. The stream is not transferable.
+    ///
+    /// Returns the id of the new stream
+    ///
+    /// ### Arguments
+    /// * `sender` - The address funding the stream
+    /// * `recipient` - The address receiving the stream
+    /// * `amount` - The amount to stream, excluding the protocol fee
+    /// * `token_address` - The address of the token to stream
+    /// * `start_time` - The time the stream starts
+    /// * `tranches` - The tranches of the stream, ordered by unlock time
+    /// * `cancellable` - If the stream can be cancelled
+    ///
+    /// ### Panics
+    /// If the contract is paused, the amount is zero or negative, the start time is in the
+    /// past, the tranches are empty, not ordered after `start_time` or do not sum to the
+    /// amount
+    fn create_with_tranches(
+        e: Env,
+        sender: Address,
+        recipient: Address,
+        amount: i128,
+        token_address: Address,
+        start_time: u64,
+        tranches: Vec<Tranche>,
+        cancellable: bool,
+    ) -> u32;
+
+    /// Withdraw tokens streamed to the recipient
+    ///
+    /// The recipient can withdraw to any address, while the sender and operators approved
+    /// by the recipient can only withdraw to the recipient.
+    ///
+    /// ### Arguments
+    /// * `caller` - The address of the sender, recipient or an operator of the recipient
+    /// * `to` - The address receiving the tokens
+    /// * `stream_id` - The id of the stream
+    /// * `amount` - The amount to withdraw
+    ///
+    /// ### Panics
+    /// If the stream does not exist, the caller is not the sender, recipient or an active
+    /// operator of the recipient, withdrawals are paused and the caller is not the
+    /// recipient, `to` is not a valid destination for the caller or the amount exceeds
+    /// the withdrawable amount
+    fn withdraw(e: Env, caller: Address, to: Address, stream_id: u32, amount: i128);
+
+    /// Withdraw all tokens currently withdrawable from a stream
+    ///
+    /// Returns the amount withdrawn
+    ///
+    /// ### Arguments
+    /// * `caller` - The address of the sender, recipient or an operator of the recipient
+    /// * `stream_id` - The id of the stream
+    /// * `to` - The address receiving the tokens
+    ///
+    /// ### Panics
+    /// If the stream does not exist, the caller is not the sender, recipient or an active
+    /// operator of the recipient, withdrawals are paused and the caller is not the
+    /// recipient, or `to` is not a valid destination for the caller
+    fn withdraw_max(e: Env, caller: Address, stream_id: u32, to: Address) -> i128;
+
+    /// Withdraw all tokens currently withdrawable from each of `stream_ids`, making a
+    /// single transfer to `to` for each token
+    ///
+    /// Returns the amount withdrawn from each stream, in the order of `stream_ids`
+    ///
+    /// ### Arguments
+    /// * `caller` - The address of the sender, recipient or an operator of the recipient
+    ///   of every stream
+    /// * `stream_ids` - The ids of the streams
+    /// * `to` - The address receiving the tokens
+    ///
+    /// ### Panics
+    /// If no or more than 20 stream ids are given, or for any stream, the stream does not
+    /// exist, the caller is not the sender, recipient or an active operator of the
+    /// recipient, withdrawals are paused and the caller is not the recipient, or `to`
+    /// is not a valid destination for the caller. Also panics if the streams withdrawn
+    /// from plus two balances per token transferred exceed 25 ledger writes.
+    fn withdraw_multiple(
+        e: Env,
+        caller: Address,
+        stream_ids: Vec<u32>,
+        to: Address,
+    ) -> Vec<i128>;
 
     fn cancel(e: Env, caller: Address, stream_id: u32);
+
+    /// Cancel each of `stream_ids`, refunding the unstreamed tokens with a single transfer
+    /// to each sender for each token
+    ///
+    /// Returns the amount refunded for each stream, in the order of `stream_ids`
+    ///
+    /// ### Arguments
+    /// * `caller` - The address of the sender or recipient of every stream
+    /// * `stream_ids` - The ids of the streams
+    /// * `skip_settled` - If streams that are already cancelled or depleted are skipped,
+    ///   refunding nothing, instead of failing the batch
+    ///
+    /// ### Panics
+    /// If no or more than 20 stream ids are given, or for any stream, the stream does not
+    /// exist, the caller is not the sender or recipient, the stream is not cancellable,
+    /// or it is already cancelled or depleted and `skip_settled` is false. Also panics if
+    /// the streams cancelled plus the balances refunded, one per sender and token and one
+    /// per token for the contract, exceed 25 ledger writes.
+    fn cancel_multiple(
+        e: Env,
+        caller: Address,
+        stream_ids: Vec<u32>,
+        skip_settled: bool,
+    ) -> Vec<i128>;
+
+    /// (Sender only) Irrevocably make a stream non-cancellable
+    ///
+    /// ### Arguments
+    /// * `stream_id` - The id of the stream
+    ///
+    /// ### Panics
+    /// If the stream does not exist, is already non-cancellable or has been cancelled
+    fn renounce(e: Env, stream_id: u32);
+
+    /// (Sender only) Pause a stream. Nothing is streamed while the stream is paused, and
+    /// the amount streamed so far can still be withdrawn.
+    ///
+    /// ### Arguments
+    /// * `stream_id` - The id of the stream
+    ///
+    /// ### Panics
+    /// If the stream does not exist, is not cancellable, is already paused, or is
+    /// cancelled, depleted or fully streamed
+    fn pause(e: Env, stream_id: u32);
+
+    /// (Sender only) Resume a paused stream. The remaining schedule is shifted forward by
+    /// the time the stream was paused for.
+    ///
+    /// ### Arguments
+    /// * `stream_id` - The id of the stream
+    ///
+    /// ### Panics
+    /// If the contract is paused, the stream does not exist or is not paused
+    fn resume(e: Env, stream_id: u32);
+
+    /// (Sender only) Add funds to a range stream and optionally extend its stop time,
+    /// charging the fee for the added amount
+    ///
+    /// Once past the cliff, the amount accrued so far is kept as the stream's cliff
+    /// amount and the schedule restarts from the current time, so the remaining deposit
+    /// streams linearly until `new_stop_time`. The stop time can only be extended as far
+    /// as the added amount keeps the remaining deposit unlocking at least as fast.
+    ///
+    /// ### Arguments
+    /// * `stream_id` - The id of the stream
+    /// * `amount` - The amount to add, excluding the protocol fee
+    /// * `new_stop_time` - The new stop time, or the current stop time to keep it
+    ///
+    /// ### Panics
+    /// If the contract is paused, the stream does not exist, is not a range stream, is
+    /// paused, cancelled, depleted or fully streamed, the amount is zero or negative, or
+    /// the new stop time is before the current stop time or would make the remaining
+    /// deposit unlock slower than before
+    fn top_up(e: Env, stream_id: u32, amount: i128, new_stop_time: u64);
+
+    /// Get the owner of a stream, who holds the right to withdraw from it
+    ///
+    /// ### Arguments
+    /// * `stream_id` - The id of the stream
+    ///
+    /// ### Panics
+    /// If the stream does not exist
+    fn owner_of(e: Env, stream_id: u32) -> Address;
+
+    /// Get the address approved to transfer a stream, if any
+    ///
+    /// ### Arguments
+    /// * `stream_id` - The id of the stream
+    fn get_approved(e: Env, stream_id: u32) -> Option<Address>;
+
+    /// Check if `operator` can transfer all of the streams owned by `owner`
+    ///
+    /// ### Arguments
+    /// * `owner` - The owner of the streams
+    /// * `operator` - The operator
+    fn is_approved_for_all(e: Env, owner: Address, operator: Address) -> bool;
+
+    /// (Owner only) Approve an address to transfer a stream, replacing any previous
+    /// approval. The approval is cleared when the stream is transferred.
+    ///
+    /// ### Arguments
+    /// * `owner` - The owner of the stream
+    /// * `approved` - The address to approve, or None to remove the approval
+    /// * `stream_id` - The id of the stream
+    ///
+    /// ### Panics
+    /// If the stream does not exist, `owner` is not its owner or it is not transferable
+    fn approve(e: Env, owner: Address, approved: Option<Address>, stream_id: u32);
+
+    /// Approve or unapprove an operator to transfer all of the streams owned by `owner`
+    ///
+    /// ### Arguments
+    /// * `owner` - The owner of the streams
+    /// * `operator` - The operator
+    /// * `approved` - If the operator is approved
+    fn approve_for_all(e: Env, owner: Address, operator: Address, approved: bool);
+
+    /// Transfer a stream to `to`, who becomes its recipient and can withdraw from it
+    ///
+    /// ### Arguments
+    /// * `from` - The owner of the stream
+    /// * `to` - The new owner of the stream
+    /// * `stream_id` - The id of the stream
+    ///
+    /// ### Panics
+    /// If the stream does not exist, is not transferable, `from` is not its owner or `to`
+    /// is the contract itself
+    fn transfer(e: Env, from: Address, to: Address, stream_id: u32);
+
+    /// Transfer a stream from `from` to `to` on behalf of its owner
+    ///
+    /// ### Arguments
+    /// * `spender` - The address approved for the stream, or an operator of `from`
+    /// * `from` - The owner of the stream
+    /// * `to` - The new owner of the stream
+    /// * `stream_id` - The id of the stream
+    ///
+    /// ### Panics
+    /// If the stream does not exist, `spender` is not approved for the stream or an
+    /// operator of `from`, the stream is not transferable, `from` is not its owner or `to`
+    /// is the contract itself
+    fn transfer_from(e: Env, spender: Address, from: Address, to: Address, stream_id: u32);
+
+    /// Get the address proposed to take over as sender of a stream, if any
+    ///
+    /// ### Arguments
+    /// * `stream_id` - The id of the stream
+    fn pending_sender(e: Env, stream_id: u32) -> Option<Address>;
+
+    /// (Sender only) Propose `new_sender` to take over as sender of a stream, replacing any
+    /// previous proposal. The sender is unchanged until `new_sender` accepts.
+    ///
+    /// ### Arguments
+    /// * `stream_id` - The id of the stream
+    /// * `new_sender` - The proposed sender
+    ///
+    /// ### Panics
+    /// If the stream does not exist
+    fn propose_sender_transfer(e: Env, stream_id: u32, new_sender: Address);
+
+    /// Accept a proposal to take over as sender of a stream, moving the rights to cancel,
+    /// pause and top up the stream and any refunds to `new_sender`
+    ///
+    /// ### Arguments
+    /// * `new_sender` - The proposed sender
+    /// * `stream_id` - The id of the stream
+    ///
+    /// ### Panics
+    /// If the stream does not exist or `new_sender` is not its proposed sender
+    fn accept_sender_transfer(e: Env, new_sender: Address, stream_id: u32);
+
+    /// Propose `new_sender` to take over as sender of each of `stream_ids`
+    ///
+    /// ### Arguments
+    /// * `sender` - The sender of every stream
+    /// * `stream_ids` - The ids of the streams
+    /// * `new_sender` - The proposed sender
+    ///
+    /// ### Panics
+    /// If no or more than 12 stream ids are given, or any stream does not exist or is
+    /// not sent by `sender`
+    fn propose_sender_transfer_multiple(
+        e: Env,
+        sender: Address,
+        stream_ids: Vec<u32>,
+        new_sender: Address,
+    );
+
+    /// Accept the proposals to take over as sender of each of `stream_ids`
+    ///
+    /// ### Arguments
+    /// * `new_sender` - The proposed sender of every stream
+    /// * `stream_ids` - The ids of the streams
+    ///
+    /// ### Panics
+    /// If no or more than 12 stream ids are given, or any stream does not exist or
+    /// `new_sender` is not its proposed sender
+    fn accept_sender_transfer_multiple(e: Env, new_sender: Address, stream_ids: Vec<u32>);
+
+    /// Check if `operator` is approved by `recipient` to withdraw on their behalf and the
+    /// approval has not expired
+    ///
+    /// ### Arguments
+    /// * `recipient` - The recipient of the streams
+    /// * `operator` - The operator
+    fn is_operator(e: Env, recipient: Address, operator: Address) -> bool;
+
+    /// Approve an operator to withdraw from every stream of `recipient` until `expiry`,
+    /// replacing any previous approval. Operators can only withdraw to the recipient.
+    ///
+    /// ### Arguments
+    /// * `recipient` - The recipient of the streams
+    /// * `operator` - The operator
+    /// * `expiry` - The time the approval expires
+    ///
+    /// ### Panics
+    /// If the expiry is not in the future
+    fn approve_operator(e: Env, recipient: Address, operator: Address, expiry: u64);
+
+    /// Revoke an operator's approval to withdraw from the streams of `recipient`
+    ///
+    /// ### Arguments
+    /// * `recipient` - The recipient of the streams
+    /// * `operator` - The operator
+    fn revoke_operator(e: Env, recipient: Address, operator: Address);
 }
@@ -18,5 +18,37 @@ pub enum StreamError {
     // Stream specific errors
     StreamDoesNotExist = 200,
     ExceedsStreamedAmount = 201,
-    
+    AssetFeeNotSet = 202,
+    InsufficientAccruedFees = 203,
+    FeeExceedsMaximum = 204,
+    InvalidFeeModel = 205,
+    NotCancellable = 206,
+    StreamSettled = 207,
+    InvalidAmount = 208,
+    StartTimeInPast = 209,
+    CliffBeforeStart = 210,
+    StopBeforeStart = 211,
+    StopBeforeCliff = 212,
+    NotSenderOrRecipient = 213,
+    NotRecipient = 214,
+    InvalidDestination = 215,
+    InvalidCliffAmount = 216,
+    InvalidSegments = 217,
+    SegmentsNotOrdered = 218,
+    SegmentAmountsMismatch = 219,
+    InvalidTranches = 220,
+    TranchesNotOrdered = 221,
+    TrancheAmountsMismatch = 222,
+    StreamPaused = 223,
+    StreamNotPaused = 224,
+    InvalidStreamKind = 225,
+    InvalidStopTime = 226,
+    InvalidBatch = 227,
+    NotTransferable = 228,
+    NotApproved = 229,
+    NotSender = 230,
+    NotPendingSender = 231,
+    InvalidExpiry = 232,
+    NoPendingAdmin = 233,
+    ContractPaused = 234,
 }
@@ -1,4 +1,6 @@
-use soroban_sdk::{Address, Env, Symbol};
+use soroban_sdk::{Address, BytesN, Env, Symbol};
+
+use crate::types::{FeeModel, Role};
 
 pub struct StreamEvents {}
 
@@ -6,21 +8,29 @@ impl StreamEvents {
     /// Emitted when a stream is created
     ///
     /// - topics - `["stream_created", stream_id: u32, sender: Address]`
-    /// - data - `[token_address: Address, recipient: Address, deposit: i128, start_time: u64, stop_time: u64]`
+    /// - data - `[token_address: Address, recipient: Address, deposit: i128, fee: i128, start_time: u64, stop_time: u64]`
     pub fn stream_created(
         e: &Env,
         stream_id: u32,
         sender: Address,
         recipient: Address,
         deposit: i128,
+        fee: i128,
         token_address: Address,
         start_time: u64,
         stop_time: u64,
     ) {
-        let topics = (Symbol::new(&e, "stream_created"), stream_id, sender);
+        let topics = (Symbol::new(e, "stream_created"), stream_id, sender);
         e.events().publish(
             topics,
-            (token_address, recipient, deposit, start_time, stop_time),
+            (
+                token_address,
+                recipient,
+                deposit,
+                fee,
+                start_time,
+                stop_time,
+            ),
         );
     }
 
@@ -29,16 +39,230 @@ impl StreamEvents {
     /// - topics - `["proposal_canceled", proposal_id: u32]`
     /// - data - ()
     pub(crate) fn stream_cancelled(e: &Env, stream_id: u32) {
-        let topics = (Symbol::new(&e, "stream_cancelled"), stream_id);
+        let topics = (Symbol::new(e, "stream_cancelled"), stream_id);
+        e.events().publish(topics, ());
+    }
+
+    /// Emitted when a stream is made non-cancellable by its sender
+    ///
+    /// - topics - `["stream_renounced", stream_id: u32]`
+    /// - data - ()
+    pub(crate) fn stream_renounced(e: &Env, stream_id: u32) {
+        let topics = (Symbol::new(e, "stream_renounced"), stream_id);
         e.events().publish(topics, ());
     }
 
-    /// Emitted when a proposal voting period is closed
+    /// Emitted when the sender adds funds to a stream
     ///
-    /// - topics - `["proposal_voting_closed", proposal_id: u32, status: u32, eta: u32]`
-    /// - data - `final_votes: VoteCount`
-    pub(crate) fn stream_withdrawn(e: &Env, recipient: Address, stream_id: u32, amount: i128) {
-        let topics = (Symbol::new(e, "stream_withdrawn"), stream_id, recipient);
+    /// - topics - `["stream_topped_up", stream_id: u32]`
+    /// - data - `[amount: i128, fee: i128, stop_time: u64]`
+    pub(crate) fn stream_topped_up(
+        e: &Env,
+        stream_id: u32,
+        amount: i128,
+        fee: i128,
+        stop_time: u64,
+    ) {
+        let topics = (Symbol::new(e, "stream_topped_up"), stream_id);
+        e.events().publish(topics, (amount, fee, stop_time));
+    }
+
+    /// Emitted when a stream is paused by its sender
+    ///
+    /// - topics - `["stream_paused", stream_id: u32]`
+    /// - data - ()
+    pub(crate) fn stream_paused(e: &Env, stream_id: u32) {
+        let topics = (Symbol::new(e, "stream_paused"), stream_id);
+        e.events().publish(topics, ());
+    }
+
+    /// Emitted when a paused stream is resumed by its sender
+    ///
+    /// - topics - `["stream_resumed", stream_id: u32]`
+    /// - data - `paused_duration: u64`
+    pub(crate) fn stream_resumed(e: &Env, stream_id: u32, paused_duration: u64) {
+        let topics = (Symbol::new(e, "stream_resumed"), stream_id);
+        e.events().publish(topics, paused_duration);
+    }
+
+    /// Emitted when tokens are withdrawn from a stream
+    ///
+    /// - topics - `["stream_withdrawn", stream_id: u32, to: Address]`
+    /// - data - `amount: i128`
+    pub(crate) fn stream_withdrawn(e: &Env, to: Address, stream_id: u32, amount: i128) {
+        let topics = (Symbol::new(e, "stream_withdrawn"), stream_id, to);
         e.events().publish(topics, amount);
     }
+
+    /// Emitted when the fee for an asset is set
+    ///
+    /// - topics - `["asset_fee_set", token_address: Address]`
+    /// - data - `fee: FeeModel`
+    pub(crate) fn asset_fee_set(e: &Env, token_address: Address, fee: FeeModel) {
+        let topics = (Symbol::new(e, "asset_fee_set"), token_address);
+        e.events().publish(topics, fee);
+    }
+
+    /// Emitted when the fee for an asset is removed
+    ///
+    /// - topics - `["asset_fee_removed", token_address: Address]`
+    /// - data - ()
+    pub(crate) fn asset_fee_removed(e: &Env, token_address: Address) {
+        let topics = (Symbol::new(e, "asset_fee_removed"), token_address);
+        e.events().publish(topics, ());
+    }
+
+    /// Emitted when accrued protocol fees are claimed
+    ///
+    /// - topics - `["fees_claimed", token_address: Address, to: Address]`
+    /// - data - `amount: i128`
+    pub(crate) fn fees_claimed(e: &Env, token_address: Address, to: Address, amount: i128) {
+        let topics = (Symbol::new(e, "fees_claimed"), token_address, to);
+        e.events().publish(topics, amount);
+    }
+
+    /// Emitted when a fee for an asset is migrated out of temporary storage
+    ///
+    /// - topics - `["asset_fee_migrated", token_address: Address]`
+    /// - data - `fee: FeeModel`
+    pub(crate) fn asset_fee_migrated(e: &Env, token_address: Address, fee: FeeModel) {
+        let topics = (Symbol::new(e, "asset_fee_migrated"), token_address);
+        e.events().publish(topics, fee);
+    }
+
+    /// Emitted when the recipient rights of a stream are transferred
+    ///
+    /// - topics - `["transfer", from: Address, to: Address]`
+    /// - data - `stream_id: u32`
+    pub(crate) fn transfer(e: &Env, from: Address, to: Address, stream_id: u32) {
+        let topics = (Symbol::new(e, "transfer"), from, to);
+        e.events().publish(topics, stream_id);
+    }
+
+    /// Emitted when an address is approved to transfer a stream, or the approval is removed
+    ///
+    /// - topics - `["approve", owner: Address, stream_id: u32]`
+    /// - data - `approved: Option<Address>`
+    pub(crate) fn approve(e: &Env, owner: Address, stream_id: u32, approved: Option<Address>) {
+        let topics = (Symbol::new(e, "approve"), owner, stream_id);
+        e.events().publish(topics, approved);
+    }
+
+    /// Emitted when an operator is approved or unapproved to transfer all of an owner's streams
+    ///
+    /// - topics - `["approve_for_all", owner: Address, operator: Address]`
+    /// - data - `approved: bool`
+    pub(crate) fn approve_for_all(e: &Env, owner: Address, operator: Address, approved: bool) {
+        let topics = (Symbol::new(e, "approve_for_all"), owner, operator);
+        e.events().publish(topics, approved);
+    }
+
+    /// Emitted when the sender of a stream proposes a new sender
+    ///
+    /// - topics - `["sender_transfer_proposed", stream_id: u32, sender: Address]`
+    /// - data - `new_sender: Address`
+    pub(crate) fn sender_transfer_proposed(
+        e: &Env,
+        stream_id: u32,
+        sender: Address,
+        new_sender: Address,
+    ) {
+        let topics = (Symbol::new(e, "sender_transfer_proposed"), stream_id, sender);
+        e.events().publish(topics, new_sender);
+    }
+
+    /// Emitted when a proposed sender accepts a stream, taking over its cancel rights and refunds
+    ///
+    /// - topics - `["sender_transferred", stream_id: u32, old_sender: Address]`
+    /// - data - `new_sender: Address`
+    pub(crate) fn sender_transferred(
+        e: &Env,
+        stream_id: u32,
+        old_sender: Address,
+        new_sender: Address,
+    ) {
+        let topics = (Symbol::new(e, "sender_transferred"), stream_id, old_sender);
+        e.events().publish(topics, new_sender);
+    }
+
+    /// Emitted when a recipient approves an operator to withdraw on their behalf
+    ///
+    /// - topics - `["operator_approved", recipient: Address, operator: Address]`
+    /// - data - `expiry: u64`
+    pub(crate) fn operator_approved(e: &Env, recipient: Address, operator: Address, expiry: u64) {
+        let topics = (Symbol::new(e, "operator_approved"), recipient, operator);
+        e.events().publish(topics, expiry);
+    }
+
+    /// Emitted when a recipient revokes an operator's approval to withdraw on their behalf
+    ///
+    /// - topics - `["operator_revoked", recipient: Address, operator: Address]`
+    /// - data - ()
+    pub(crate) fn operator_revoked(e: &Env, recipient: Address, operator: Address) {
+        let topics = (Symbol::new(e, "operator_revoked"), recipient, operator);
+        e.events().publish(topics, ());
+    }
+
+    /// Emitted when the admin proposes a new admin
+    ///
+    /// - topics - `["admin_proposed", admin: Address]`
+    /// - data - `new_admin: Address`
+    pub(crate) fn admin_proposed(e: &Env, admin: Address, new_admin: Address) {
+        let topics = (Symbol::new(e, "admin_proposed"), admin);
+        e.events().publish(topics, new_admin);
+    }
+
+    /// Emitted when a proposed admin accepts and takes over as admin
+    ///
+    /// - topics - `["admin_changed", old_admin: Address]`
+    /// - data - `new_admin: Address`
+    pub(crate) fn admin_changed(e: &Env, old_admin: Address, new_admin: Address) {
+        let topics = (Symbol::new(e, "admin_changed"), old_admin);
+        e.events().publish(topics, new_admin);
+    }
+
+    /// Emitted when the admin grants a role to an account
+    ///
+    /// - topics - `["role_granted", account: Address]`
+    /// - data - `role: Role`
+    pub(crate) fn role_granted(e: &Env, account: Address, role: Role) {
+        let topics = (Symbol::new(e, "role_granted"), account);
+        e.events().publish(topics, role);
+    }
+
+    /// Emitted when the admin revokes a role from an account
+    ///
+    /// - topics - `["role_revoked", account: Address]`
+    /// - data - `role: Role`
+    pub(crate) fn role_revoked(e: &Env, account: Address, role: Role) {
+        let topics = (Symbol::new(e, "role_revoked"), account);
+        e.events().publish(topics, role);
+    }
+
+    /// Emitted when the contract's wasm is upgraded
+    ///
+    /// - topics - `["contract_upgraded", caller: Address]`
+    /// - data - `wasm_hash: BytesN<32>`
+    pub(crate) fn contract_upgraded(e: &Env, caller: Address, wasm_hash: BytesN<32>) {
+        let topics = (Symbol::new(e, "contract_upgraded"), caller);
+        e.events().publish(topics, wasm_hash);
+    }
+
+    /// Emitted when the contract is paused
+    ///
+    /// - topics - `["contract_paused", caller: Address]`
+    /// - data - `pause_withdrawals: bool`
+    pub(crate) fn contract_paused(e: &Env, caller: Address, pause_withdrawals: bool) {
+        let topics = (Symbol::new(e, "contract_paused"), caller);
+        e.events().publish(topics, pause_withdrawals);
+    }
+
+    /// Emitted when the contract is unpaused
+    ///
+    /// - topics - `["contract_unpaused", caller: Address]`
+    /// - data - ()
+    pub(crate) fn contract_unpaused(e: &Env, caller: Address) {
+        let topics = (Symbol::new(e, "contract_unpaused"), caller);
+        e.events().publish(topics, ());
+    }
 }
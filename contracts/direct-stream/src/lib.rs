@@ -1,4 +1,5 @@
 #![no_std]
+#![allow(clippy::too_many_arguments)]
 
 #[cfg(any(test, feature = "testutils"))]
 extern crate std;
@@ -1,15 +1,18 @@
 use soroban_sdk::{
-    contracttype, unwrap::UnwrapOptimized, Address, Env, IntoVal, Symbol, TryFromVal, Val,
+    contracttype, unwrap::UnwrapOptimized, Address, Env, IntoVal, Symbol, TryFromVal, Val, Vec,
 };
 
 use crate::{
     constants::ONE_DAY_LEDGERS,
-    types::{StreamSettings, DirectStreamData},
+    types::{DirectStreamData, FeeModel, Role, Segment, StreamSettings, Tranche},
 };
 
 const SETTINGS_KEY: &str = "Settings";
 const IS_INIT_KEY: &str = "IsInit";
 const STREAM_ID_KEY: &str = "StreamId";
+const PENDING_ADMIN_KEY: &str = "PendingAdmin";
+const PAUSED_KEY: &str = "Paused";
+const WITHDRAWALS_PAUSED_KEY: &str = "WithdrawalsPaused";
 
 const LEDGER_THRESHOLD_INSTANCE: u32 = ONE_DAY_LEDGERS * 30; // ~ 30 days
 const LEDGER_BUMP_INSTANCE: u32 = LEDGER_THRESHOLD_INSTANCE + ONE_DAY_LEDGERS; // ~ 31 days
@@ -27,6 +30,22 @@ pub struct VoterStatusKey {
     pub voter: Address,
 }
 
+// Key for storing an operator approved to transfer all of an owner's streams
+#[derive(Clone)]
+#[contracttype]
+pub struct OperatorKey {
+    pub owner: Address,
+    pub operator: Address,
+}
+
+// Key for storing if an account holds a role
+#[derive(Clone)]
+#[contracttype]
+pub struct RoleKey {
+    pub role: Role,
+    pub account: Address,
+}
+
 #[derive(Clone)]
 #[contracttype]
 pub enum StreamDataKey {
@@ -34,6 +53,22 @@ pub enum StreamDataKey {
     Streams(u32),
     // A map of the fee to the asset address
     AssetFee(Address),
+    // A map of the fees accrued by the protocol to the asset address
+    AccruedFees(Address),
+    // A map of the segments of a segmented stream to the stream id
+    Segments(u32),
+    // A map of the tranches of a tranched stream to the stream id
+    Tranches(u32),
+    // A map of the address approved to transfer a stream to the stream id
+    Approval(u32),
+    // A map of whether an operator can transfer all of an owner's streams
+    Operator(OperatorKey),
+    // A map of the address proposed to take over as sender to the stream id
+    PendingSender(u32),
+    // A map of the time an operator's approval to withdraw for a recipient expires
+    WithdrawOperator(OperatorKey),
+    // A map of whether an account holds a role, kept in instance storage
+    Role(RoleKey),
 }
 
 //********** Storage Utils **********//
@@ -84,17 +119,106 @@ pub fn set_is_init(e: &Env) {
 pub fn set_settings(e: &Env, settings: &StreamSettings) {
     e.storage()
         .instance()
-        .set::<Symbol, StreamSettings>(&Symbol::new(&e, SETTINGS_KEY), &settings);
+        .set::<Symbol, StreamSettings>(&Symbol::new(e, SETTINGS_KEY), settings);
 }
 
 /// Get the contract settings
 pub fn get_settings(e: &Env) -> StreamSettings {
     e.storage()
         .instance()
-        .get::<Symbol, StreamSettings>(&Symbol::new(&e, SETTINGS_KEY))
+        .get::<Symbol, StreamSettings>(&Symbol::new(e, SETTINGS_KEY))
         .unwrap_optimized()
 }
 
+/// Check if the contract is paused
+pub fn get_paused(e: &Env) -> bool {
+    e.storage()
+        .instance()
+        .get::<Symbol, bool>(&Symbol::new(e, PAUSED_KEY))
+        .unwrap_or(false)
+}
+
+/// Set if the contract is paused
+///
+/// ### Arguments
+/// * `paused` - If the contract is paused
+pub fn set_paused(e: &Env, paused: bool) {
+    e.storage()
+        .instance()
+        .set::<Symbol, bool>(&Symbol::new(e, PAUSED_KEY), &paused);
+}
+
+/// Check if withdrawals by anyone but the recipient are paused
+pub fn get_withdrawals_paused(e: &Env) -> bool {
+    e.storage()
+        .instance()
+        .get::<Symbol, bool>(&Symbol::new(e, WITHDRAWALS_PAUSED_KEY))
+        .unwrap_or(false)
+}
+
+/// Set if withdrawals by anyone but the recipient are paused
+///
+/// ### Arguments
+/// * `paused` - If withdrawals by anyone but the recipient are paused
+pub fn set_withdrawals_paused(e: &Env, paused: bool) {
+    e.storage()
+        .instance()
+        .set::<Symbol, bool>(&Symbol::new(e, WITHDRAWALS_PAUSED_KEY), &paused);
+}
+
+/// Get the address proposed to take over as admin, if any
+pub fn get_pending_admin(e: &Env) -> Option<Address> {
+    e.storage()
+        .instance()
+        .get::<Symbol, Address>(&Symbol::new(e, PENDING_ADMIN_KEY))
+}
+
+/// Set the address proposed to take over as admin, or remove the proposal
+///
+/// ### Arguments
+/// * `new_admin` - The proposed admin, or None to remove the proposal
+pub fn set_pending_admin(e: &Env, new_admin: &Option<Address>) {
+    let key = Symbol::new(e, PENDING_ADMIN_KEY);
+    match new_admin {
+        Some(new_admin) => e
+            .storage()
+            .instance()
+            .set::<Symbol, Address>(&key, new_admin),
+        None => e.storage().instance().remove(&key),
+    }
+}
+
+/// Check if `account` holds `role`
+///
+/// ### Arguments
+/// * `role` - The role
+/// * `account` - The account
+pub fn has_role(e: &Env, role: &Role, account: &Address) -> bool {
+    let key = StreamDataKey::Role(RoleKey {
+        role: role.clone(),
+        account: account.clone(),
+    });
+    e.storage().instance().has(&key)
+}
+
+/// Grant or revoke `role` for `account`
+///
+/// ### Arguments
+/// * `role` - The role
+/// * `account` - The account
+/// * `granted` - If the role is granted
+pub fn set_role(e: &Env, role: &Role, account: &Address, granted: bool) {
+    let key = StreamDataKey::Role(RoleKey {
+        role: role.clone(),
+        account: account.clone(),
+    });
+    if granted {
+        e.storage().instance().set::<StreamDataKey, bool>(&key, &true);
+    } else {
+        e.storage().instance().remove(&key);
+    }
+}
+
 /********** Persistent **********/
 
 /// Set the next stream id and bump if necessary
@@ -102,7 +226,7 @@ pub fn get_settings(e: &Env) -> StreamSettings {
 /// ### Arguments
 /// * `stream_id` - The new stream_id
 pub fn set_next_stream_id(e: &Env, stream_id: u32) {
-    let key = Symbol::new(&e, STREAM_ID_KEY);
+    let key = Symbol::new(e, STREAM_ID_KEY);
     e.storage()
         .persistent()
         .set::<Symbol, u32>(&key, &stream_id);
@@ -113,50 +237,339 @@ pub fn set_next_stream_id(e: &Env, stream_id: u32) {
 
 /// Get the current stream id
 pub fn get_next_stream_id(e: &Env) -> u32 {
-    let key = Symbol::new(&e, STREAM_ID_KEY);
-    get_persistent_default::<Symbol, u32>(&e, &key, 1_u32, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER)
+    let key = Symbol::new(e, STREAM_ID_KEY);
+    get_persistent_default::<Symbol, u32>(e, &key, 1_u32, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER)
 }
 
 pub fn get_stream(e: &Env, stream_id: &u32) -> Option<DirectStreamData> {
-    let key = StreamDataKey::Streams(stream_id.clone());
+    let key = StreamDataKey::Streams(*stream_id);
     e.storage()
         .persistent()
         .get::<StreamDataKey, DirectStreamData>(&key)
 }
 
 pub fn set_stream(e: &Env, stream_id: &u32, stream: &DirectStreamData) {
-    let key = StreamDataKey::Streams(stream_id.clone());
+    let key = StreamDataKey::Streams(*stream_id);
     e.storage()
         .persistent()
-        .set::<StreamDataKey, DirectStreamData>(&key, &stream);
+        .set::<StreamDataKey, DirectStreamData>(&key, stream);
     e.storage()
         .persistent()
         .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
 }
 
-/********** Temporary **********/
+/// Get the segments of a segmented stream
+///
+/// ### Arguments
+/// * `stream_id` - The id of the stream
+pub fn get_segments(e: &Env, stream_id: &u32) -> Vec<Segment> {
+    let key = StreamDataKey::Segments(*stream_id);
+    let segments = e
+        .storage()
+        .persistent()
+        .get::<StreamDataKey, Vec<Segment>>(&key)
+        .unwrap_optimized();
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
+    segments
+}
 
-/***** Stream Config *****/
+/// Set the segments of a segmented stream
+///
+/// ### Arguments
+/// * `stream_id` - The id of the stream
+/// * `segments` - The segments of the stream
+pub fn set_segments(e: &Env, stream_id: &u32, segments: &Vec<Segment>) {
+    let key = StreamDataKey::Segments(*stream_id);
+    e.storage()
+        .persistent()
+        .set::<StreamDataKey, Vec<Segment>>(&key, segments);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
+}
 
-/// Fetch stream config at `proposal_id`
+/// Get the tranches of a tranched stream
 ///
 /// ### Arguments
-/// * `proposal_id` - The id of the proposal to fetch
-pub fn get_asset_fee(e: &Env, token_address: &Address) -> Option<i128> {
-    let key = StreamDataKey::AssetFee(token_address.clone());
+/// * `stream_id` - The id of the stream
+pub fn get_tranches(e: &Env, stream_id: &u32) -> Vec<Tranche> {
+    let key = StreamDataKey::Tranches(*stream_id);
+    let tranches = e
+        .storage()
+        .persistent()
+        .get::<StreamDataKey, Vec<Tranche>>(&key)
+        .unwrap_optimized();
     e.storage()
-        .temporary()
-        .get::<StreamDataKey, i128>(&key)
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
+    tranches
 }
 
-pub fn set_asset_fee(e: &Env, token_address: Address, fee: &i128) {
-    let key = StreamDataKey::AssetFee(token_address);
+/// Set the tranches of a tranched stream
+///
+/// ### Arguments
+/// * `stream_id` - The id of the stream
+/// * `tranches` - The tranches of the stream
+pub fn set_tranches(e: &Env, stream_id: &u32, tranches: &Vec<Tranche>) {
+    let key = StreamDataKey::Tranches(*stream_id);
     e.storage()
-        .temporary()
-        .set::<StreamDataKey, i128>(&key, &fee);
+        .persistent()
+        .set::<StreamDataKey, Vec<Tranche>>(&key, tranches);
     e.storage()
-        .temporary()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
+}
+
+/// Get the address approved to transfer a stream, if any
+///
+/// ### Arguments
+/// * `stream_id` - The id of the stream
+pub fn get_approval(e: &Env, stream_id: &u32) -> Option<Address> {
+    let key = StreamDataKey::Approval(*stream_id);
+    e.storage()
+        .persistent()
+        .get::<StreamDataKey, Address>(&key)
+}
+
+/// Set the address approved to transfer a stream, or remove the approval
+///
+/// ### Arguments
+/// * `stream_id` - The id of the stream
+/// * `approved` - The approved address, or None to remove the approval
+pub fn set_approval(e: &Env, stream_id: &u32, approved: &Option<Address>) {
+    let key = StreamDataKey::Approval(*stream_id);
+    match approved {
+        Some(approved) => {
+            e.storage()
+                .persistent()
+                .set::<StreamDataKey, Address>(&key, approved);
+            e.storage()
+                .persistent()
+                .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
+        }
+        None => e.storage().persistent().remove(&key),
+    }
+}
+
+/// Check if `operator` can transfer all of `owner`'s streams
+///
+/// ### Arguments
+/// * `owner` - The owner of the streams
+/// * `operator` - The operator
+pub fn get_operator(e: &Env, owner: &Address, operator: &Address) -> bool {
+    let key = StreamDataKey::Operator(OperatorKey {
+        owner: owner.clone(),
+        operator: operator.clone(),
+    });
+    e.storage()
+        .persistent()
+        .get::<StreamDataKey, bool>(&key)
+        .unwrap_or(false)
+}
+
+/// Set if `operator` can transfer all of `owner`'s streams
+///
+/// ### Arguments
+/// * `owner` - The owner of the streams
+/// * `operator` - The operator
+/// * `approved` - If the operator is approved
+pub fn set_operator(e: &Env, owner: &Address, operator: &Address, approved: bool) {
+    let key = StreamDataKey::Operator(OperatorKey {
+        owner: owner.clone(),
+        operator: operator.clone(),
+    });
+    if approved {
+        e.storage()
+            .persistent()
+            .set::<StreamDataKey, bool>(&key, &true);
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
+    } else {
+        e.storage().persistent().remove(&key);
+    }
+}
+
+/// Get the address proposed to take over as sender of a stream, if any
+///
+/// ### Arguments
+/// * `stream_id` - The id of the stream
+pub fn get_pending_sender(e: &Env, stream_id: &u32) -> Option<Address> {
+    let key = StreamDataKey::PendingSender(*stream_id);
+    e.storage()
+        .persistent()
+        .get::<StreamDataKey, Address>(&key)
+}
+
+/// Set the address proposed to take over as sender of a stream, or remove the proposal
+///
+/// ### Arguments
+/// * `stream_id` - The id of the stream
+/// * `new_sender` - The proposed sender, or None to remove the proposal
+pub fn set_pending_sender(e: &Env, stream_id: &u32, new_sender: &Option<Address>) {
+    let key = StreamDataKey::PendingSender(*stream_id);
+    match new_sender {
+        Some(new_sender) => {
+            e.storage()
+                .persistent()
+                .set::<StreamDataKey, Address>(&key, new_sender);
+            e.storage()
+                .persistent()
+                .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
+        }
+        None => e.storage().persistent().remove(&key),
+    }
+}
+
+/// Get the time `operator`'s approval to withdraw on behalf of `recipient` expires, if any
+///
+/// ### Arguments
+/// * `recipient` - The recipient of the streams
+/// * `operator` - The operator
+pub fn get_withdraw_operator(e: &Env, recipient: &Address, operator: &Address) -> Option<u64> {
+    let key = StreamDataKey::WithdrawOperator(OperatorKey {
+        owner: recipient.clone(),
+        operator: operator.clone(),
+    });
+    e.storage().persistent().get::<StreamDataKey, u64>(&key)
+}
+
+/// Set the time `operator`'s approval to withdraw on behalf of `recipient` expires, or
+/// remove the approval
+///
+/// ### Arguments
+/// * `recipient` - The recipient of the streams
+/// * `operator` - The operator
+/// * `expiry` - The time the approval expires, or None to remove the approval
+pub fn set_withdraw_operator(
+    e: &Env,
+    recipient: &Address,
+    operator: &Address,
+    expiry: &Option<u64>,
+) {
+    let key = StreamDataKey::WithdrawOperator(OperatorKey {
+        owner: recipient.clone(),
+        operator: operator.clone(),
+    });
+    match expiry {
+        Some(expiry) => {
+            e.storage()
+                .persistent()
+                .set::<StreamDataKey, u64>(&key, expiry);
+            e.storage()
+                .persistent()
+                .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
+        }
+        None => e.storage().persistent().remove(&key),
+    }
+}
+
+/// Get the fees accrued by the protocol for `token_address`
+///
+/// ### Arguments
+/// * `token_address` - The address of the token
+pub fn get_accrued_fees(e: &Env, token_address: &Address) -> i128 {
+    let key = StreamDataKey::AccruedFees(token_address.clone());
+    get_persistent_default::<StreamDataKey, i128>(
+        e,
+        &key,
+        0,
+        LEDGER_THRESHOLD_USER,
+        LEDGER_BUMP_USER,
+    )
+}
+
+/// Set the fees accrued by the protocol for `token_address`
+///
+/// ### Arguments
+/// * `token_address` - The address of the token
+/// * `amount` - The accrued fee amount
+pub fn set_accrued_fees(e: &Env, token_address: &Address, amount: &i128) {
+    let key = StreamDataKey::AccruedFees(token_address.clone());
+    e.storage()
+        .persistent()
+        .set::<StreamDataKey, i128>(&key, amount);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
+}
+
+/***** Asset Fees *****/
+
+/// Fetch the fee configured for `token_address` and bump it if it exists. A fee still
+/// in temporary storage from a previous version of the contract is moved to persistent
+/// storage the first time it is read.
+///
+/// ### Arguments
+/// * `token_address` - The address of the token
+pub fn get_asset_fee(e: &Env, token_address: &Address) -> Option<FeeModel> {
+    let key = StreamDataKey::AssetFee(token_address.clone());
+    let result = e
+        .storage()
+        .persistent()
+        .get::<StreamDataKey, FeeModel>(&key);
+    if result.is_some() {
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
+        return result;
+    }
+
+    let legacy_fee = get_legacy_asset_fee(e, token_address);
+    if let Some(fee) = &legacy_fee {
+        set_asset_fee(e, token_address, fee);
+        del_legacy_asset_fee(e, token_address);
+    }
+    legacy_fee
+}
+
+/// Set the fee for `token_address`
+///
+/// ### Arguments
+/// * `token_address` - The address of the token
+/// * `fee` - The fee model used when a stream of the token is created
+pub fn set_asset_fee(e: &Env, token_address: &Address, fee: &FeeModel) {
+    let key = StreamDataKey::AssetFee(token_address.clone());
+    e.storage()
+        .persistent()
+        .set::<StreamDataKey, FeeModel>(&key, fee);
+    e.storage()
+        .persistent()
         .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
 }
 
+/// Remove the fee for `token_address`
+///
+/// ### Arguments
+/// * `token_address` - The address of the token
+pub fn del_asset_fee(e: &Env, token_address: &Address) {
+    let key = StreamDataKey::AssetFee(token_address.clone());
+    e.storage().persistent().remove::<StreamDataKey>(&key);
+}
+
+/********** Temporary **********/
+
+/// Fetch a flat fee for `token_address` written to temporary storage by a previous
+/// version of the contract, if it has not expired yet
+///
+/// ### Arguments
+/// * `token_address` - The address of the token
+pub fn get_legacy_asset_fee(e: &Env, token_address: &Address) -> Option<FeeModel> {
+    let key = StreamDataKey::AssetFee(token_address.clone());
+    e.storage()
+        .temporary()
+        .get::<StreamDataKey, i128>(&key)
+        .map(FeeModel::Flat)
+}
 
+/// Remove a fee for `token_address` written to temporary storage by a previous
+/// version of the contract
+///
+/// ### Arguments
+/// * `token_address` - The address of the token
+pub fn del_legacy_asset_fee(e: &Env, token_address: &Address) {
+    let key = StreamDataKey::AssetFee(token_address.clone());
+    e.storage().temporary().remove::<StreamDataKey>(&key);
+}
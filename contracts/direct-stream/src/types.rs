@@ -7,28 +7,117 @@ pub struct StreamSettings {
     /// The address of the admin that can set protocol fees and perform other administrative functions.
     pub admin: Address,
     /// The fee that will apply if the token being streamed doesn't have a fee set.
-    pub base_fee: i128,
+    pub base_fee: FeeModel,
+}
+
+/// A narrow administrative power the admin can grant to other addresses
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub enum Role {
+    /// Can set, remove and migrate asset fees
+    FeeManager,
+    /// Can pause and unpause the contract
+    Pauser,
+    /// Can upgrade the contract's wasm
+    Upgrader,
+}
+
+/// The model used to calculate the fee charged when a stream is created
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub enum FeeModel {
+    /// A flat fee, in units of the token
+    Flat(i128),
+    /// A fee in basis points of the deposit
+    Bps(u32),
+    /// A fee in basis points of the deposit, clamped to `(bps, min_fee, max_fee)`
+    ClampedBps(u32, i128, i128),
+}
+
+/// The schedule a stream releases its deposit on
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub enum StreamKind {
+    /// Streams linearly from `start_time` to `stop_time`, with an optional cliff
+    Range,
+    /// Streams through a list of segments stored alongside the stream
+    Segments,
+    /// Unlocks a list of tranches stored alongside the stream
+    Tranches,
+}
+
+/// The parameters of a range stream created as part of a batch
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct RangeParams {
+    pub recipient: Address,
+    pub amount: i128,
+    pub start_time: u64,
+    pub stop_time: u64,
+    pub cliff_time: u64,
+    pub cliff_amount: i128,
+    pub cancellable: bool,
+    pub transferable: bool,
+}
+
+/// The curve a segment streams its amount along
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub enum SegmentExponent {
+    /// Streams at a constant rate
+    Linear,
+    /// Streams slowly at first and accelerates, as `elapsed^exponent`
+    Convex(u32),
+    /// Streams quickly at first and decelerates, as `1 - (1 - elapsed)^exponent`
+    Concave(u32),
+}
+
+/// A portion of a segmented stream that streams `amount` from the end of the previous
+/// segment, or the stream start time for the first segment, until `end_time`
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct Segment {
+    pub amount: i128,
+    pub exponent: SegmentExponent,
+    pub end_time: u64,
+}
+
+/// A portion of a tranched stream that unlocks `amount` all at once at `unlock_time`
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct Tranche {
+    pub amount: i128,
+    pub unlock_time: u64,
 }
 
 #[derive(Clone, Debug)]
 #[contracttype]
 pub struct DirectStreamData {
     pub id: u32,
+    pub kind: StreamKind,
     pub sender: Address,
     pub recipient: Address,
     pub start_time: u64,
     pub cliff_time: u64,
     pub stop_time: u64,
     pub deposit: i128,
+    /// The amount unlocked at `cliff_time`, on top of the amount streamed linearly since `start_time`
+    pub cliff_amount: i128,
+    pub fee: i128,
     pub withdrawn: i128,
     pub refunded: i128,
     pub is_cancellable: bool,
+    /// If the recipient can transfer the right to withdraw from the stream
+    pub is_transferable: bool,
     pub is_cancelled: bool,
     pub is_depleted: bool,
+    pub is_paused: bool,
+    /// The time the stream was paused at, if it is paused
+    pub paused_time: u64,
     pub token_address: Address,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 #[contracttype]
 pub enum Status {
     Pending, // Stream has been created but not started yet
@@ -37,4 +126,5 @@ pub enum Status {
     Cancelled, // Stream has been cancelled. Funds have been refunded to the sender but recipient is yet to withdraw the funds
     Cliff, // Stream has started but has not reached the cliff time yet
     Depleted, // Stream has been completed or cancelled and all funds have been withdrawn
+    Paused, // Stream has been paused by the sender and is not streaming until it is resumed
 }
@@ -0,0 +1,393 @@
+use sep_41_token::TokenClient;
+use soroban_sdk::{contract, contractimpl, panic_with_error, Address, Env};
+
+use crate::{
+    errors::FlowStreamError,
+    events::FlowStreamEvents,
+    flow_stream::FlowStream,
+    storage,
+    types::{FlowStreamData, SenderBalance, Solvency},
+};
+
+/// Get the debt accrued at `rate_per_second` since `snapshot_time` on top of `snapshot_debt`
+fn get_accrued_debt(
+    e: &Env,
+    rate_per_second: i128,
+    snapshot_debt: i128,
+    snapshot_time: u64,
+) -> i128 {
+    let elapsed_time = (e.ledger().timestamp() - snapshot_time) as i128;
+    rate_per_second
+        .checked_mul(elapsed_time)
+        .and_then(|accrued_debt| accrued_debt.checked_add(snapshot_debt))
+        .unwrap_or_else(|| panic_with_error!(e, FlowStreamError::OverflowError))
+}
+
+/// Get the debt owed to the recipient, including debt accrued since the last snapshot
+fn get_total_debt(e: &Env, stream: &FlowStreamData) -> i128 {
+    if stream.is_paused {
+        return stream.snapshot_debt;
+    }
+
+    get_accrued_debt(
+        e,
+        stream.rate_per_second,
+        stream.snapshot_debt,
+        stream.snapshot_time,
+    )
+}
+
+/// Get the debt owed across all of the sender's streams of the token, including debt
+/// accrued since the last snapshot
+fn get_balance_debt(e: &Env, balance: &SenderBalance) -> i128 {
+    get_accrued_debt(
+        e,
+        balance.rate_per_second,
+        balance.snapshot_debt,
+        balance.snapshot_time,
+    )
+}
+
+/// Get the debt owed to the recipient that is covered by the sender's balance. Streams
+/// share the balance, so once it runs dry debt is paid out to whoever withdraws first.
+fn get_withdrawable_amount(e: &Env, stream: &FlowStreamData, balance: &SenderBalance) -> i128 {
+    get_total_debt(e, stream).min(balance.balance)
+}
+
+/// Get the time from which the sender's balance no longer covers the debt
+fn get_depletion_time(balance: &SenderBalance) -> Option<u64> {
+    if balance.rate_per_second == 0 {
+        return None;
+    }
+    if balance.balance < balance.snapshot_debt {
+        return Some(balance.snapshot_time);
+    }
+
+    let covered_time = (balance.balance - balance.snapshot_debt) / balance.rate_per_second;
+    Some(
+        balance
+            .snapshot_time
+            .saturating_add(u64::try_from(covered_time).unwrap_or(u64::MAX))
+            .saturating_add(1),
+    )
+}
+
+/// Record the debt accrued so far, so the stream can continue accruing from now
+fn snapshot_debt(e: &Env, stream: &mut FlowStreamData) {
+    stream.snapshot_debt = get_total_debt(e, stream);
+    stream.snapshot_time = e.ledger().timestamp();
+}
+
+/// Record the debt accrued so far across the sender's streams, so the balance can
+/// continue accruing from now
+fn snapshot_balance_debt(e: &Env, balance: &mut SenderBalance) {
+    balance.snapshot_debt = get_balance_debt(e, balance);
+    balance.snapshot_time = e.ledger().timestamp();
+}
+
+/// Add `rate_delta` to the combined rate of the sender's balance
+fn adjust_balance_rate(e: &Env, balance: &mut SenderBalance, rate_delta: i128) {
+    balance.rate_per_second = balance
+        .rate_per_second
+        .checked_add(rate_delta)
+        .unwrap_or_else(|| panic_with_error!(e, FlowStreamError::OverflowError));
+}
+
+fn load_stream(e: &Env, stream_id: u32) -> FlowStreamData {
+    storage::get_stream(e, &stream_id)
+        .unwrap_or_else(|| panic_with_error!(e, FlowStreamError::StreamDoesNotExist))
+}
+
+fn load_sender_balance(e: &Env, stream: &FlowStreamData) -> SenderBalance {
+    storage::get_sender_balance(e, &stream.sender, &stream.token_address)
+}
+
+/********** Modifiers **********/
+
+fn require_sender_or_recipient(e: &Env, stream: &FlowStreamData, user: &Address) {
+    if *user != stream.sender && *user != stream.recipient {
+        panic_with_error!(e, FlowStreamError::NotSenderOrRecipient);
+    }
+}
+
+fn require_positive_amount(e: &Env, amount: i128) {
+    if amount <= 0 {
+        panic_with_error!(e, FlowStreamError::InvalidAmount);
+    }
+}
+
+fn require_positive_rate(e: &Env, rate_per_second: i128) {
+    if rate_per_second <= 0 {
+        panic_with_error!(e, FlowStreamError::InvalidRate);
+    }
+}
+
+/// Require `to` to be a valid destination for a withdrawal made by `caller`. The
+/// recipient can withdraw to any address, while the sender can only withdraw to the recipient.
+fn require_valid_destination(e: &Env, stream: &FlowStreamData, caller: &Address, to: &Address) {
+    if *to == e.current_contract_address() {
+        panic_with_error!(e, FlowStreamError::InvalidDestination);
+    }
+    if *caller != stream.recipient && *to != stream.recipient {
+        panic_with_error!(e, FlowStreamError::NotRecipient);
+    }
+}
+
+/// Withdraw `amount` of covered debt from `stream` to `to`, paid out of the sender's `balance`
+fn withdraw_from_stream(
+    e: &Env,
+    stream: &mut FlowStreamData,
+    balance: &mut SenderBalance,
+    to: &Address,
+    amount: i128,
+) {
+    snapshot_debt(e, stream);
+    snapshot_balance_debt(e, balance);
+    stream.snapshot_debt -= amount;
+    balance.snapshot_debt -= amount;
+    balance.balance -= amount;
+
+    storage::set_stream(e, &stream.id, stream);
+    storage::set_sender_balance(e, &stream.sender, &stream.token_address, balance);
+
+    transfer(
+        e,
+        &e.current_contract_address(),
+        to,
+        amount,
+        &stream.token_address,
+    );
+
+    FlowStreamEvents::stream_withdrawn(e, stream.id, to.clone(), amount);
+}
+
+// Transfer tokens between the contract and a user
+fn transfer(e: &Env, from: &Address, to: &Address, amount: i128, token_address: &Address) {
+    let token_client = TokenClient::new(e, token_address);
+    token_client.transfer(from, to, &amount);
+}
+
+#[contract]
+pub struct FlowStreamContract;
+
+#[contractimpl]
+#[allow(clippy::needless_pass_by_value)]
+impl FlowStream for FlowStreamContract {
+    fn create(
+        e: Env,
+        sender: Address,
+        recipient: Address,
+        token_address: Address,
+        rate_per_second: i128,
+    ) -> u32 {
+        sender.require_auth();
+        storage::extend_instance(&e);
+        require_positive_rate(&e, rate_per_second);
+
+        let stream_id = storage::get_next_stream_id(&e);
+        let stream = FlowStreamData {
+            id: stream_id,
+            sender: sender.clone(),
+            recipient: recipient.clone(),
+            token_address: token_address.clone(),
+            rate_per_second,
+            snapshot_debt: 0,
+            snapshot_time: e.ledger().timestamp(),
+            is_paused: false,
+        };
+
+        let mut balance = load_sender_balance(&e, &stream);
+        snapshot_balance_debt(&e, &mut balance);
+        adjust_balance_rate(&e, &mut balance, rate_per_second);
+
+        storage::set_stream(&e, &stream_id, &stream);
+        storage::set_sender_balance(&e, &sender, &token_address, &balance);
+        storage::set_next_stream_id(&e, stream_id + 1);
+
+        FlowStreamEvents::stream_created(
+            &e,
+            stream_id,
+            sender,
+            recipient,
+            token_address,
+            rate_per_second,
+        );
+        stream_id
+    }
+
+    fn deposit(e: Env, sender: Address, token_address: Address, amount: i128) {
+        sender.require_auth();
+        storage::extend_instance(&e);
+        require_positive_amount(&e, amount);
+
+        transfer(
+            &e,
+            &sender,
+            &e.current_contract_address(),
+            amount,
+            &token_address,
+        );
+
+        let mut balance = storage::get_sender_balance(&e, &sender, &token_address);
+        balance.balance += amount;
+        storage::set_sender_balance(&e, &sender, &token_address, &balance);
+
+        FlowStreamEvents::balance_deposited(&e, sender, token_address, amount);
+    }
+
+    fn withdraw(e: Env, caller: Address, to: Address, stream_id: u32, amount: i128) {
+        caller.require_auth();
+        storage::extend_instance(&e);
+        require_positive_amount(&e, amount);
+
+        let mut stream = load_stream(&e, stream_id);
+
+        require_sender_or_recipient(&e, &stream, &caller);
+        require_valid_destination(&e, &stream, &caller, &to);
+
+        let mut balance = load_sender_balance(&e, &stream);
+        if amount > get_withdrawable_amount(&e, &stream, &balance) {
+            panic_with_error!(&e, FlowStreamError::ExceedsWithdrawableAmount);
+        }
+
+        withdraw_from_stream(&e, &mut stream, &mut balance, &to, amount);
+    }
+
+    fn withdraw_max(e: Env, caller: Address, stream_id: u32, to: Address) -> i128 {
+        caller.require_auth();
+        storage::extend_instance(&e);
+
+        let mut stream = load_stream(&e, stream_id);
+
+        require_sender_or_recipient(&e, &stream, &caller);
+        require_valid_destination(&e, &stream, &caller, &to);
+
+        let mut balance = load_sender_balance(&e, &stream);
+        let amount = get_withdrawable_amount(&e, &stream, &balance);
+        if amount > 0 {
+            withdraw_from_stream(&e, &mut stream, &mut balance, &to, amount);
+        }
+        amount
+    }
+
+    fn withdraw_balance(e: Env, sender: Address, token_address: Address, amount: i128) {
+        sender.require_auth();
+        storage::extend_instance(&e);
+        require_positive_amount(&e, amount);
+
+        let mut balance = storage::get_sender_balance(&e, &sender, &token_address);
+
+        let refundable_amount = balance.balance - get_balance_debt(&e, &balance);
+        if amount > refundable_amount {
+            panic_with_error!(&e, FlowStreamError::ExceedsRefundableBalance);
+        }
+
+        balance.balance -= amount;
+        storage::set_sender_balance(&e, &sender, &token_address, &balance);
+
+        transfer(
+            &e,
+            &e.current_contract_address(),
+            &sender,
+            amount,
+            &token_address,
+        );
+
+        FlowStreamEvents::balance_withdrawn(&e, sender, token_address, amount);
+    }
+
+    fn adjust_rate(e: Env, stream_id: u32, rate_per_second: i128) {
+        storage::extend_instance(&e);
+        require_positive_rate(&e, rate_per_second);
+
+        let mut stream = load_stream(&e, stream_id);
+        stream.sender.require_auth();
+
+        if stream.is_paused {
+            panic_with_error!(&e, FlowStreamError::StreamPaused);
+        }
+
+        let mut balance = load_sender_balance(&e, &stream);
+        snapshot_debt(&e, &mut stream);
+        snapshot_balance_debt(&e, &mut balance);
+        adjust_balance_rate(&e, &mut balance, rate_per_second - stream.rate_per_second);
+        stream.rate_per_second = rate_per_second;
+        storage::set_stream(&e, &stream_id, &stream);
+        storage::set_sender_balance(&e, &stream.sender, &stream.token_address, &balance);
+
+        FlowStreamEvents::rate_adjusted(&e, stream_id, rate_per_second);
+    }
+
+    fn pause(e: Env, stream_id: u32) {
+        storage::extend_instance(&e);
+
+        let mut stream = load_stream(&e, stream_id);
+        stream.sender.require_auth();
+
+        if stream.is_paused {
+            panic_with_error!(&e, FlowStreamError::StreamPaused);
+        }
+
+        let mut balance = load_sender_balance(&e, &stream);
+        snapshot_debt(&e, &mut stream);
+        snapshot_balance_debt(&e, &mut balance);
+        adjust_balance_rate(&e, &mut balance, -stream.rate_per_second);
+        stream.is_paused = true;
+        storage::set_stream(&e, &stream_id, &stream);
+        storage::set_sender_balance(&e, &stream.sender, &stream.token_address, &balance);
+
+        FlowStreamEvents::stream_paused(&e, stream_id, stream.snapshot_debt);
+    }
+
+    fn resume(e: Env, stream_id: u32) {
+        storage::extend_instance(&e);
+
+        let mut stream = load_stream(&e, stream_id);
+        stream.sender.require_auth();
+
+        if !stream.is_paused {
+            panic_with_error!(&e, FlowStreamError::StreamNotPaused);
+        }
+
+        // debt accrues again from now on
+        let mut balance = load_sender_balance(&e, &stream);
+        snapshot_balance_debt(&e, &mut balance);
+        adjust_balance_rate(&e, &mut balance, stream.rate_per_second);
+        stream.snapshot_time = e.ledger().timestamp();
+        stream.is_paused = false;
+        storage::set_stream(&e, &stream_id, &stream);
+        storage::set_sender_balance(&e, &stream.sender, &stream.token_address, &balance);
+
+        FlowStreamEvents::stream_resumed(&e, stream_id);
+    }
+
+    fn get_stream(e: Env, stream_id: u32) -> Option<FlowStreamData> {
+        storage::get_stream(&e, &stream_id)
+    }
+
+    fn get_sender_balance(e: Env, sender: Address, token_address: Address) -> SenderBalance {
+        storage::get_sender_balance(&e, &sender, &token_address)
+    }
+
+    fn total_debt(e: Env, stream_id: u32) -> i128 {
+        let stream = load_stream(&e, stream_id);
+        get_total_debt(&e, &stream)
+    }
+
+    fn withdrawable_amount(e: Env, stream_id: u32) -> i128 {
+        let stream = load_stream(&e, stream_id);
+        let balance = load_sender_balance(&e, &stream);
+        get_withdrawable_amount(&e, &stream, &balance)
+    }
+
+    fn solvency(e: Env, sender: Address, token_address: Address) -> Solvency {
+        let balance = storage::get_sender_balance(&e, &sender, &token_address);
+        let total_debt = get_balance_debt(&e, &balance);
+        Solvency {
+            balance: balance.balance,
+            total_debt,
+            uncovered_debt: (total_debt - balance.balance).max(0),
+            depletion_time: get_depletion_time(&balance),
+        }
+    }
+}
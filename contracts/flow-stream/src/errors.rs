@@ -0,0 +1,26 @@
+use soroban_sdk::contracterror;
+
+/// The error codes for the contract.
+#[contracterror]
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum FlowStreamError {
+    // Default errors to align with built-in contract
+    InternalError = 1,
+
+    UnauthorizedError = 4,
+
+    NegativeAmountError = 8,
+    OverflowError = 12,
+
+    // Stream specific errors
+    StreamDoesNotExist = 200,
+    ExceedsWithdrawableAmount = 201,
+    InvalidAmount = 202,
+    InvalidRate = 203,
+    ExceedsRefundableBalance = 204,
+    StreamPaused = 205,
+    StreamNotPaused = 206,
+    NotSenderOrRecipient = 207,
+    NotRecipient = 208,
+    InvalidDestination = 209,
+}
@@ -0,0 +1,87 @@
+use soroban_sdk::{Address, Env, Symbol};
+
+pub struct FlowStreamEvents {}
+
+impl FlowStreamEvents {
+    /// Emitted when a stream is created
+    ///
+    /// - topics - `["stream_created", stream_id: u32, sender: Address]`
+    /// - data - `[token_address: Address, recipient: Address, rate_per_second: i128]`
+    pub(crate) fn stream_created(
+        e: &Env,
+        stream_id: u32,
+        sender: Address,
+        recipient: Address,
+        token_address: Address,
+        rate_per_second: i128,
+    ) {
+        let topics = (Symbol::new(e, "stream_created"), stream_id, sender);
+        e.events()
+            .publish(topics, (token_address, recipient, rate_per_second));
+    }
+
+    /// Emitted when a sender deposits tokens into their balance
+    ///
+    /// - topics - `["balance_deposited", sender: Address, token_address: Address]`
+    /// - data - `amount: i128`
+    pub(crate) fn balance_deposited(
+        e: &Env,
+        sender: Address,
+        token_address: Address,
+        amount: i128,
+    ) {
+        let topics = (Symbol::new(e, "balance_deposited"), sender, token_address);
+        e.events().publish(topics, amount);
+    }
+
+    /// Emitted when tokens are withdrawn from a stream by the recipient
+    ///
+    /// - topics - `["stream_withdrawn", stream_id: u32, to: Address]`
+    /// - data - `amount: i128`
+    pub(crate) fn stream_withdrawn(e: &Env, stream_id: u32, to: Address, amount: i128) {
+        let topics = (Symbol::new(e, "stream_withdrawn"), stream_id, to);
+        e.events().publish(topics, amount);
+    }
+
+    /// Emitted when a sender withdraws tokens from their balance that are not owed to
+    /// the recipients of their streams
+    ///
+    /// - topics - `["balance_withdrawn", sender: Address, token_address: Address]`
+    /// - data - `amount: i128`
+    pub(crate) fn balance_withdrawn(
+        e: &Env,
+        sender: Address,
+        token_address: Address,
+        amount: i128,
+    ) {
+        let topics = (Symbol::new(e, "balance_withdrawn"), sender, token_address);
+        e.events().publish(topics, amount);
+    }
+
+    /// Emitted when the rate of a stream is adjusted
+    ///
+    /// - topics - `["rate_adjusted", stream_id: u32]`
+    /// - data - `rate_per_second: i128`
+    pub(crate) fn rate_adjusted(e: &Env, stream_id: u32, rate_per_second: i128) {
+        let topics = (Symbol::new(e, "rate_adjusted"), stream_id);
+        e.events().publish(topics, rate_per_second);
+    }
+
+    /// Emitted when a stream is paused
+    ///
+    /// - topics - `["stream_paused", stream_id: u32]`
+    /// - data - `total_debt: i128`
+    pub(crate) fn stream_paused(e: &Env, stream_id: u32, total_debt: i128) {
+        let topics = (Symbol::new(e, "stream_paused"), stream_id);
+        e.events().publish(topics, total_debt);
+    }
+
+    /// Emitted when a stream is resumed
+    ///
+    /// - topics - `["stream_resumed", stream_id: u32]`
+    /// - data - ()
+    pub(crate) fn stream_resumed(e: &Env, stream_id: u32) {
+        let topics = (Symbol::new(e, "stream_resumed"), stream_id);
+        e.events().publish(topics, ());
+    }
+}
@@ -0,0 +1,162 @@
+use soroban_sdk::{contractclient, Address, Env};
+
+use crate::types::{FlowStreamData, SenderBalance, Solvency};
+
+/// Open-ended streams funded from a balance each sender shares across their streams.
+///
+/// The contract has no admin, so it is not covered by the direct stream contract's pause:
+/// pausing direct streams leaves flow streams open to deposits, withdrawals and new streams.
+#[contractclient(name = "FlowStreamClient")]
+pub trait FlowStream {
+    /// Create an open-ended stream of `token_address` from the sender to the recipient
+    ///
+    /// The stream accrues debt to the recipient at `rate_per_second` from the time it is
+    /// created, and has no stop time. The recipient can withdraw the debt covered by the
+    /// balance the sender deposited for the token, which is shared by all of the sender's
+    /// streams of that token.
+    ///
+    /// Returns the id of the new stream
+    ///
+    /// ### Arguments
+    /// * `sender` - The address of the sender
+    /// * `recipient` - The address receiving the stream
+    /// * `token_address` - The address of the token to stream
+    /// * `rate_per_second` - The amount of tokens streamed each second
+    ///
+    /// ### Panics
+    /// If the rate is zero or negative
+    fn create(
+        e: Env,
+        sender: Address,
+        recipient: Address,
+        token_address: Address,
+        rate_per_second: i128,
+    ) -> u32;
+
+    /// Deposit tokens into the sender's balance, which funds all of the sender's
+    /// streams of `token_address`
+    ///
+    /// ### Arguments
+    /// * `sender` - The address of the sender
+    /// * `token_address` - The address of the token to deposit
+    /// * `amount` - The amount to deposit
+    ///
+    /// ### Panics
+    /// If the amount is zero or negative
+    fn deposit(e: Env, sender: Address, token_address: Address, amount: i128);
+
+    /// Withdraw debt of a stream covered by the sender's balance
+    ///
+    /// The recipient can withdraw to any address, while the sender can only
+    /// withdraw to the recipient.
+    ///
+    /// ### Arguments
+    /// * `caller` - The address of the sender or recipient of the stream
+    /// * `to` - The address receiving the tokens
+    /// * `stream_id` - The id of the stream
+    /// * `amount` - The amount to withdraw
+    ///
+    /// ### Panics
+    /// If the stream does not exist, the caller is not the sender or recipient, `to`
+    /// is not a valid destination for the caller or the amount exceeds the
+    /// withdrawable amount
+    fn withdraw(e: Env, caller: Address, to: Address, stream_id: u32, amount: i128);
+
+    /// Withdraw all debt of a stream currently covered by the sender's balance
+    ///
+    /// Returns the amount withdrawn
+    ///
+    /// ### Arguments
+    /// * `caller` - The address of the sender or recipient of the stream
+    /// * `stream_id` - The id of the stream
+    /// * `to` - The address receiving the tokens
+    ///
+    /// ### Panics
+    /// If the stream does not exist, the caller is not the sender or recipient or `to`
+    /// is not a valid destination for the caller
+    fn withdraw_max(e: Env, caller: Address, stream_id: u32, to: Address) -> i128;
+
+    /// Withdraw tokens from the sender's balance that are not owed to the recipients of
+    /// the sender's streams
+    ///
+    /// ### Arguments
+    /// * `sender` - The address of the sender
+    /// * `token_address` - The address of the token to withdraw
+    /// * `amount` - The amount to withdraw
+    ///
+    /// ### Panics
+    /// If the amount is zero or negative, or the amount exceeds the balance left after
+    /// the debt owed across the sender's streams of the token
+    fn withdraw_balance(e: Env, sender: Address, token_address: Address, amount: i128);
+
+    /// (Sender only) Change the rate of a stream. Debt accrued at the previous rate
+    /// is kept.
+    ///
+    /// ### Arguments
+    /// * `stream_id` - The id of the stream
+    /// * `rate_per_second` - The new amount of tokens streamed each second
+    ///
+    /// ### Panics
+    /// If the stream does not exist, is paused or the rate is zero or negative
+    fn adjust_rate(e: Env, stream_id: u32, rate_per_second: i128);
+
+    /// (Sender only) Stop a stream from accruing debt. Debt accrued so far is kept and
+    /// can still be withdrawn.
+    ///
+    /// ### Arguments
+    /// * `stream_id` - The id of the stream
+    ///
+    /// ### Panics
+    /// If the stream does not exist or is already paused
+    fn pause(e: Env, stream_id: u32);
+
+    /// (Sender only) Resume accruing debt on a paused stream at its rate
+    ///
+    /// ### Arguments
+    /// * `stream_id` - The id of the stream
+    ///
+    /// ### Panics
+    /// If the stream does not exist or is not paused
+    fn resume(e: Env, stream_id: u32);
+
+    /// Get a stream
+    ///
+    /// ### Arguments
+    /// * `stream_id` - The id of the stream
+    fn get_stream(e: Env, stream_id: u32) -> Option<FlowStreamData>;
+
+    /// Get the balance of `sender` for `token_address`
+    ///
+    /// ### Arguments
+    /// * `sender` - The address of the sender
+    /// * `token_address` - The address of the token
+    fn get_sender_balance(e: Env, sender: Address, token_address: Address) -> SenderBalance;
+
+    /// Get the debt owed to the recipient, whether or not it is covered by the sender's
+    /// balance
+    ///
+    /// ### Arguments
+    /// * `stream_id` - The id of the stream
+    ///
+    /// ### Panics
+    /// If the stream does not exist
+    fn total_debt(e: Env, stream_id: u32) -> i128;
+
+    /// Get the debt owed to the recipient that is covered by the sender's balance. Once
+    /// the balance runs dry, it pays out whichever of the sender's streams withdraws first.
+    ///
+    /// ### Arguments
+    /// * `stream_id` - The id of the stream
+    ///
+    /// ### Panics
+    /// If the stream does not exist
+    fn withdrawable_amount(e: Env, stream_id: u32) -> i128;
+
+    /// Get the solvency of the sender's balance for `token_address` across all of the
+    /// sender's streams of that token
+    ///
+    /// ### Arguments
+    /// * `sender` - The address of the sender
+    /// * `token_address` - The address of the token
+    fn solvency(e: Env, sender: Address, token_address: Address) -> Solvency;
+}
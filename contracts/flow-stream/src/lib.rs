@@ -0,0 +1,14 @@
+#![no_std]
+
+#[cfg(any(test, feature = "testutils"))]
+extern crate std;
+
+pub mod constants;
+pub mod contract;
+pub mod errors;
+pub mod events;
+pub mod flow_stream;
+pub mod storage;
+pub mod types;
+
+pub use contract::*;
@@ -0,0 +1,151 @@
+use soroban_sdk::{contracttype, Address, Env, IntoVal, Symbol, TryFromVal, Val};
+
+use crate::{
+    constants::ONE_DAY_LEDGERS,
+    types::{FlowStreamData, SenderBalance},
+};
+
+const STREAM_ID_KEY: &str = "StreamId";
+
+const LEDGER_THRESHOLD_INSTANCE: u32 = ONE_DAY_LEDGERS * 30; // ~ 30 days
+const LEDGER_BUMP_INSTANCE: u32 = LEDGER_THRESHOLD_INSTANCE + ONE_DAY_LEDGERS; // ~ 31 days
+
+const LEDGER_THRESHOLD_USER: u32 = ONE_DAY_LEDGERS * 100; // ~ 100 days
+const LEDGER_BUMP_USER: u32 = LEDGER_THRESHOLD_USER + 20 * ONE_DAY_LEDGERS; // ~ 120 days
+
+//********** Storage Keys **********//
+
+#[derive(Clone)]
+#[contracttype]
+pub struct BalanceKey {
+    pub sender: Address,
+    pub token_address: Address,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub enum FlowStreamDataKey {
+    // A map of the stream to the id
+    Streams(u32),
+    // A map of the sender and token to the sender's balance
+    Balances(BalanceKey),
+}
+
+//********** Storage Utils **********//
+
+/// Bump the instance lifetime by the defined amount
+pub fn extend_instance(e: &Env) {
+    e.storage()
+        .instance()
+        .extend_ttl(LEDGER_THRESHOLD_INSTANCE, LEDGER_BUMP_INSTANCE);
+}
+
+/// Fetch an entry in persistent storage that has a default value if it doesn't exist
+fn get_persistent_default<K: IntoVal<Env, Val>, V: TryFromVal<Env, Val>>(
+    e: &Env,
+    key: &K,
+    default: V,
+    bump_threshold: u32,
+    bump_amount: u32,
+) -> V {
+    if let Some(result) = e.storage().persistent().get::<K, V>(key) {
+        e.storage()
+            .persistent()
+            .extend_ttl(key, bump_threshold, bump_amount);
+        result
+    } else {
+        default
+    }
+}
+
+/********** Persistent **********/
+
+/// Set the next stream id and bump if necessary
+///
+/// ### Arguments
+/// * `stream_id` - The new stream_id
+pub fn set_next_stream_id(e: &Env, stream_id: u32) {
+    let key = Symbol::new(e, STREAM_ID_KEY);
+    e.storage()
+        .persistent()
+        .set::<Symbol, u32>(&key, &stream_id);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
+}
+
+/// Get the current stream id
+pub fn get_next_stream_id(e: &Env) -> u32 {
+    let key = Symbol::new(e, STREAM_ID_KEY);
+    get_persistent_default::<Symbol, u32>(e, &key, 1_u32, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER)
+}
+
+/// Get a stream
+///
+/// ### Arguments
+/// * `stream_id` - The id of the stream
+pub fn get_stream(e: &Env, stream_id: &u32) -> Option<FlowStreamData> {
+    let key = FlowStreamDataKey::Streams(*stream_id);
+    e.storage()
+        .persistent()
+        .get::<FlowStreamDataKey, FlowStreamData>(&key)
+}
+
+/// Set a stream and bump its lifetime
+///
+/// ### Arguments
+/// * `stream_id` - The id of the stream
+/// * `stream` - The stream
+pub fn set_stream(e: &Env, stream_id: &u32, stream: &FlowStreamData) {
+    let key = FlowStreamDataKey::Streams(*stream_id);
+    e.storage()
+        .persistent()
+        .set::<FlowStreamDataKey, FlowStreamData>(&key, stream);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
+}
+
+/// Get the balance of `sender` for `token_address`, or an empty balance if the sender
+/// has not deposited or streamed the token
+///
+/// ### Arguments
+/// * `sender` - The address of the sender
+/// * `token_address` - The address of the token
+pub fn get_sender_balance(e: &Env, sender: &Address, token_address: &Address) -> SenderBalance {
+    let key = FlowStreamDataKey::Balances(BalanceKey {
+        sender: sender.clone(),
+        token_address: token_address.clone(),
+    });
+    get_persistent_default::<FlowStreamDataKey, SenderBalance>(
+        e,
+        &key,
+        SenderBalance::default(),
+        LEDGER_THRESHOLD_USER,
+        LEDGER_BUMP_USER,
+    )
+}
+
+/// Set the balance of `sender` for `token_address` and bump its lifetime
+///
+/// ### Arguments
+/// * `sender` - The address of the sender
+/// * `token_address` - The address of the token
+/// * `balance` - The sender's balance
+pub fn set_sender_balance(
+    e: &Env,
+    sender: &Address,
+    token_address: &Address,
+    balance: &SenderBalance,
+) {
+    let key = FlowStreamDataKey::Balances(BalanceKey {
+        sender: sender.clone(),
+        token_address: token_address.clone(),
+    });
+    e.storage()
+        .persistent()
+        .set::<FlowStreamDataKey, SenderBalance>(&key, balance);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
+}
@@ -0,0 +1,49 @@
+use soroban_sdk::{contracttype, Address};
+
+/// An open-ended stream that accrues debt to the recipient at a rate per second,
+/// paid out of the balance the sender deposited for the token
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct FlowStreamData {
+    pub id: u32,
+    pub sender: Address,
+    pub recipient: Address,
+    pub token_address: Address,
+    /// The amount of tokens streamed to the recipient each second
+    pub rate_per_second: i128,
+    /// The debt owed to the recipient as of `snapshot_time`
+    pub snapshot_debt: i128,
+    /// The time the debt was last snapshotted, from which it accrues at `rate_per_second`
+    pub snapshot_time: u64,
+    pub is_paused: bool,
+}
+
+/// The balance a sender deposited for a token, which pays out the debt of all of the
+/// sender's streams of that token
+#[derive(Clone, Debug, Default)]
+#[contracttype]
+pub struct SenderBalance {
+    /// The amount of tokens deposited and not yet withdrawn or refunded
+    pub balance: i128,
+    /// The combined rate of the sender's unpaused streams of the token
+    pub rate_per_second: i128,
+    /// The debt owed across the sender's streams of the token as of `snapshot_time`
+    pub snapshot_debt: i128,
+    /// The time the debt was last snapshotted, from which it accrues at `rate_per_second`
+    pub snapshot_time: u64,
+}
+
+/// The solvency of a sender's balance for a token at the current time
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct Solvency {
+    /// The amount of tokens deposited and not yet withdrawn or refunded
+    pub balance: i128,
+    /// The debt owed across the sender's streams of the token
+    pub total_debt: i128,
+    /// The debt that is not covered by the balance
+    pub uncovered_debt: i128,
+    /// The time from which the balance no longer covers the debt, or `None` if all of
+    /// the sender's streams of the token are paused and the debt does not grow
+    pub depletion_time: Option<u64>,
+}
@@ -8,4 +8,4 @@ mod metadata;
 mod storage_types;
 mod test;
 
-pub use crate::contract::TokenClient;
+pub use crate::contract::{Token, TokenClient};
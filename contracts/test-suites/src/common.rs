@@ -1,10 +1,22 @@
 use sep_41_token::testutils::{MockTokenClient, MockTokenWASM};
-use soroban_sdk::{testutils::Address as _, Address, Env, IntoVal};
+use soroban_sdk::{
+    testutils::{Address as _, Events},
+    Address, Env, IntoVal, Symbol, TryFromVal,
+};
+
+use crate::ONE_DAY_LEDGERS;
 
 //********** Token **********//
 
 pub fn create_stellar_token<'a>(e: &Env, admin: &Address) -> (Address, MockTokenClient<'a>) {
     let contract_id = e.register_stellar_asset_contract(admin.clone());
+    // the stellar asset contract does not bump its own instance, so keep it live
+    // for tests that jump far into the future
+    e.as_contract(&contract_id, || {
+        e.storage()
+            .instance()
+            .extend_ttl(300 * ONE_DAY_LEDGERS, 300 * ONE_DAY_LEDGERS)
+    });
     let client = MockTokenClient::new(e, &contract_id);
     (contract_id, client)
 }
@@ -26,3 +38,21 @@ pub fn create_token<'a>(
     );
     (contract_id.clone(), client)
 }
+
+//********** Events **********//
+
+/// Count the events emitted by `contract` with `name` as their first topic
+pub fn count_events(e: &Env, contract: &Address, name: &str) -> u32 {
+    let name = Symbol::new(e, name);
+    e.events()
+        .all()
+        .iter()
+        .filter(|(address, topics, _)| {
+            address == contract
+                && topics
+                    .get(0)
+                    .map(|topic| Symbol::try_from_val(e, &topic) == Ok(name.clone()))
+                    .unwrap_or(false)
+        })
+        .count() as u32
+}
@@ -1,9 +1,13 @@
-use soroban_sdk::{testutils::Address as _, Address, Env};
+use soroban_sdk::{testutils::Address as _, Address, BytesN, Env};
 
-use zentra_direct_stream::{types::StreamSettings, DirectStreamContract, DirectStreamContractClient};
+use zentra_direct_stream::{
+    types::{FeeModel, StreamSettings},
+    DirectStreamContract, DirectStreamContractClient,
+};
  
 use crate::common;
 
+#[allow(clippy::too_many_arguments)]
 mod direct_stream_contract_wasm {
     soroban_sdk::contractimport!(
         file = "../../target/wasm32-unknown-unknown/optimized/zentra_direct_stream.wasm"
@@ -17,17 +21,16 @@ mod direct_stream_contract_wasm {
 /// ### Arguments
 /// * `admin` - The address of the admin
 /// * `settings` - The settings for the direct_stream
-pub fn create_direct_stream<'a>(
+pub fn create_direct_stream(
     e: &Env,
     admin: &Address,
     settings: &StreamSettings,
 ) -> (Address, Address) {
     let stream_address = e.register_contract(None, DirectStreamContract {});
     let (underlying_token, _) = common::create_stellar_token(e, admin);
-    let stream_client: DirectStreamContractClient<'a> =
-    DirectStreamContractClient::new(&e, &stream_address);
+    let stream_client = DirectStreamContractClient::new(e, &stream_address);
     stream_client.initialize(settings);
-    return (stream_address, underlying_token);
+    (stream_address, underlying_token)
 }
 
 /// Create a direct stream contract with the wasm contract
@@ -37,23 +40,30 @@ pub fn create_direct_stream<'a>(
 /// ### Arguments
 /// * `admin` - The address of the admin
 /// * `settings` - The settings for the direct_stream
-pub fn create_direct_stream_wasm<'a>(
+pub fn create_direct_stream_wasm(
     e: &Env,
     admin: &Address,
     settings: &StreamSettings,
 ) -> (Address, Address) {
     let direct_stream_address = e.register_contract_wasm(None, direct_stream_contract_wasm::WASM);
     let (underlying_token, _) = common::create_stellar_token(e, admin);
-    let direct_stream_client: DirectStreamContractClient<'a> =
-        DirectStreamContractClient::new(&e, &direct_stream_address);
+    let direct_stream_client = DirectStreamContractClient::new(e, &direct_stream_address);
     direct_stream_client.initialize(settings);
-    return (direct_stream_address, underlying_token);
+    (direct_stream_address, underlying_token)
+}
+
+/// Upload the direct stream wasm
+///
+/// Returns the hash of the uploaded wasm
+pub fn upload_direct_stream_wasm(e: &Env) -> BytesN<32> {
+    e.deployer()
+        .upload_contract_wasm(direct_stream_contract_wasm::WASM)
 }
 
 /// Default direct stream settings
 pub fn default_stream_settings(e: &Env) -> StreamSettings {
     StreamSettings {
         admin: Address::generate(e),
-        base_fee: 1_000,
+        base_fee: FeeModel::Flat(1_000),
     }
 }
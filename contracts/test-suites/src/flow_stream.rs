@@ -0,0 +1,17 @@
+use soroban_sdk::{Address, Env};
+
+use zentra_flow_stream::FlowStreamContract;
+
+use crate::common;
+
+/// Create a flow stream contract
+///
+/// Returns (flow_stream, underlying_token)
+///
+/// ### Arguments
+/// * `admin` - The address of the admin of the underlying token
+pub fn create_flow_stream(e: &Env, admin: &Address) -> (Address, Address) {
+    let stream_address = e.register_contract(None, FlowStreamContract {});
+    let (underlying_token, _) = common::create_stellar_token(e, admin);
+    (stream_address, underlying_token)
+}
@@ -1,5 +1,6 @@
 pub mod common;
 pub mod env;
 pub mod direct_stream;
+pub mod flow_stream;
 
 pub const ONE_DAY_LEDGERS: u32 = 17280; // assumes 5s a ledger
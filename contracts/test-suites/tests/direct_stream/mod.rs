@@ -1,4 +1,21 @@
+mod test_admin;
+mod test_asset_fee;
 mod test_cancel;
+mod test_cancel_multiple;
+mod test_claim_fees;
+mod test_cliff;
 mod test_initialize;
+mod test_operator;
+mod test_pause;
 mod test_create_range;
-mod test_withdraw;
\ No newline at end of file
+mod test_create_range_batch;
+mod test_renounce;
+mod test_segments;
+mod test_sender_transfer;
+mod test_set_paused;
+mod test_streamed_amount;
+mod test_top_up;
+mod test_transfer;
+mod test_tranches;
+mod test_withdraw;
+mod test_withdraw_multiple;
\ No newline at end of file
@@ -0,0 +1,163 @@
+#[cfg(test)]
+use soroban_sdk::{
+    testutils::{Address as _, Events},
+    vec, Address, Env, Error, IntoVal, Symbol,
+};
+use test_suites::{
+    direct_stream::{
+        create_direct_stream, create_direct_stream_wasm, default_stream_settings,
+        upload_direct_stream_wasm,
+    },
+    env::EnvTestUtils,
+};
+use zentra_direct_stream::{
+    types::{FeeModel, Role},
+    DirectStreamContractClient,
+};
+
+#[test]
+fn test_admin_transfer() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let everest = Address::generate(&e);
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    let result = stream_client.try_accept_admin();
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(233))));
+
+    stream_client.propose_admin(&everest);
+    let events = e.events().all();
+    assert_eq!(
+        events.slice(events.len() - 1..),
+        vec![
+            &e,
+            (
+                stream_address.clone(),
+                (Symbol::new(&e, "admin_proposed"), settings.admin.clone()).into_val(&e),
+                everest.into_val(&e)
+            )
+        ]
+    );
+
+    // the admin is unchanged until the proposed admin accepts
+    assert_eq!(stream_client.pending_admin(), Some(everest.clone()));
+    assert_eq!(stream_client.settings().admin, settings.admin);
+
+    stream_client.accept_admin();
+    let events = e.events().all();
+    assert_eq!(
+        events.slice(events.len() - 1..),
+        vec![
+            &e,
+            (
+                stream_address.clone(),
+                (Symbol::new(&e, "admin_changed"), settings.admin.clone()).into_val(&e),
+                everest.into_val(&e)
+            )
+        ]
+    );
+    assert_eq!(stream_client.settings().admin, everest);
+    assert_eq!(stream_client.pending_admin(), None);
+
+    // the previous admin loses its powers
+    let result =
+        stream_client.try_set_asset_fee(&settings.admin, &token_address, &FeeModel::Flat(0));
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(4))));
+    stream_client.set_asset_fee(&everest, &token_address, &FeeModel::Flat(0));
+}
+
+#[test]
+fn test_roles() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let denali = Address::generate(&e);
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    let result = stream_client.try_set_asset_fee(&denali, &token_address, &FeeModel::Flat(0));
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(4))));
+
+    // the admin holds every role implicitly
+    for role in [Role::FeeManager, Role::Pauser, Role::Upgrader] {
+        assert!(stream_client.has_role(&role, &settings.admin));
+    }
+
+    stream_client.grant_role(&Role::FeeManager, &denali);
+    let events = e.events().all();
+    assert_eq!(
+        events.slice(events.len() - 1..),
+        vec![
+            &e,
+            (
+                stream_address.clone(),
+                (Symbol::new(&e, "role_granted"), denali.clone()).into_val(&e),
+                Role::FeeManager.into_val(&e)
+            )
+        ]
+    );
+    assert!(stream_client.has_role(&Role::FeeManager, &denali));
+    assert!(!stream_client.has_role(&Role::Upgrader, &denali));
+
+    // a fee manager can manage fees, but nothing else
+    stream_client.set_asset_fee(&denali, &token_address, &FeeModel::Flat(0));
+    stream_client.remove_asset_fee(&denali, &token_address);
+    let result = stream_client.try_upgrade(&denali, &upload_direct_stream_wasm(&e));
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(4))));
+
+    stream_client.revoke_role(&Role::FeeManager, &denali);
+    let events = e.events().all();
+    assert_eq!(
+        events.slice(events.len() - 1..),
+        vec![
+            &e,
+            (
+                stream_address.clone(),
+                (Symbol::new(&e, "role_revoked"), denali.clone()).into_val(&e),
+                Role::FeeManager.into_val(&e)
+            )
+        ]
+    );
+    assert!(!stream_client.has_role(&Role::FeeManager, &denali));
+    let result = stream_client.try_set_asset_fee(&denali, &token_address, &FeeModel::Flat(0));
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(4))));
+}
+
+#[test]
+fn test_upgrade() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let denali = Address::generate(&e);
+    let settings = default_stream_settings(&e);
+    let (stream_address, _) = create_direct_stream_wasm(&e, &settings.admin, &settings);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    let wasm_hash = upload_direct_stream_wasm(&e);
+    stream_client.grant_role(&Role::Upgrader, &denali);
+    e.budget().reset_unlimited();
+    stream_client.upgrade(&denali, &wasm_hash);
+    let events = e.events().all();
+    assert_eq!(
+        events.slice(events.len() - 1..),
+        vec![
+            &e,
+            (
+                stream_address.clone(),
+                (Symbol::new(&e, "contract_upgraded"), denali.clone()).into_val(&e),
+                wasm_hash.into_val(&e)
+            )
+        ]
+    );
+
+    // state is kept across the upgrade
+    assert_eq!(stream_client.settings().admin, settings.admin);
+    assert!(stream_client.has_role(&Role::Upgrader, &denali));
+}
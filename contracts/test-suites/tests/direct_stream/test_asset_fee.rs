@@ -0,0 +1,399 @@
+#[cfg(test)]
+use sep_41_token::testutils::MockTokenClient;
+use soroban_sdk::{
+    testutils::{Address as _, AuthorizedFunction, AuthorizedInvocation},
+    vec, Address, Env, IntoVal, Symbol,
+};
+use test_suites::{
+    direct_stream::{create_direct_stream, default_stream_settings},
+    env::EnvTestUtils,
+    ONE_DAY_LEDGERS,
+};
+use zentra_direct_stream::{storage::StreamDataKey, types::FeeModel, DirectStreamContractClient};
+
+#[test]
+fn test_set_asset_fee() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    let fee = 5_000_i128;
+    stream_client.set_asset_fee(&settings.admin, &token_address, &FeeModel::Flat(fee));
+
+    // verify auths
+    assert_eq!(
+        e.auths()[0],
+        (
+            settings.admin.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    stream_address.clone(),
+                    Symbol::new(&e, "set_asset_fee"),
+                    vec![
+                        &e,
+                        settings.admin.to_val(),
+                        token_address.to_val(),
+                        FeeModel::Flat(fee).into_val(&e)
+                    ]
+                )),
+                sub_invocations: std::vec![]
+            }
+        )
+    );
+
+    // verify chain results
+    assert_eq!(
+        stream_client.asset_fee(&token_address),
+        Some(FeeModel::Flat(fee))
+    );
+
+    // verify the fee is charged on stream creation
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    token_client.mint(&yosemite, &100_000_000);
+
+    let deadline = e.ledger().timestamp() + (365 * ONE_DAY_LEDGERS) as u64;
+    stream_client.create_range(
+        &yosemite,
+        &everest,
+        &10_000_000,
+        &token_address,
+        &e.ledger().timestamp(),
+        &deadline,
+        &true,
+        &e.ledger().timestamp(),
+        &0,
+        &false,
+    );
+    assert_eq!(
+        token_client.balance(&yosemite),
+        100_000_000 - 10_000_000 - fee
+    );
+    assert_eq!(token_client.balance(&stream_address), 10_000_000 + fee);
+    assert_eq!(stream_client.get_stream(&1).unwrap().fee, fee);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #8)")]
+fn test_set_asset_fee_negative() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    stream_client.set_asset_fee(&settings.admin, &token_address, &FeeModel::Flat(-1));
+}
+
+#[test]
+fn test_remove_asset_fee() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    stream_client.set_asset_fee(&settings.admin, &token_address, &FeeModel::Flat(5_000));
+    stream_client.remove_asset_fee(&settings.admin, &token_address);
+
+    // verify auths
+    assert_eq!(
+        e.auths()[0],
+        (
+            settings.admin.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    stream_address.clone(),
+                    Symbol::new(&e, "remove_asset_fee"),
+                    vec![&e, settings.admin.to_val(), token_address.to_val()]
+                )),
+                sub_invocations: std::vec![]
+            }
+        )
+    );
+
+    // verify chain results
+    assert_eq!(stream_client.asset_fee(&token_address), None);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #202)")]
+fn test_remove_asset_fee_not_set() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    stream_client.remove_asset_fee(&settings.admin, &token_address);
+}
+
+#[test]
+fn test_quote_fee() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    // falls back to the base fee
+    assert_eq!(stream_client.quote_fee(&token_address, &10_000_000), 1_000);
+
+    // uses the asset fee once set
+    stream_client.set_asset_fee(&settings.admin, &token_address, &FeeModel::Flat(0));
+    assert_eq!(stream_client.quote_fee(&token_address, &10_000_000), 0);
+
+    stream_client.remove_asset_fee(&settings.admin, &token_address);
+    assert_eq!(stream_client.quote_fee(&token_address, &10_000_000), 1_000);
+}
+
+#[test]
+fn test_quote_fee_bps() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    // 0.25% of the deposit, rounded up
+    stream_client.set_asset_fee(&settings.admin, &token_address, &FeeModel::Bps(25));
+    assert_eq!(stream_client.quote_fee(&token_address, &10_000_000), 25_000);
+    assert_eq!(stream_client.quote_fee(&token_address, &10_001), 26);
+
+    // 0.25% of the deposit, clamped between 100 and 10_000
+    stream_client.set_asset_fee(
+        &settings.admin,
+        &token_address,
+        &FeeModel::ClampedBps(25, 100, 10_000),
+    );
+    assert_eq!(stream_client.quote_fee(&token_address, &10_000), 100);
+    assert_eq!(stream_client.quote_fee(&token_address, &1_000_000), 2_500);
+    assert_eq!(stream_client.quote_fee(&token_address, &10_000_000), 10_000);
+}
+
+#[test]
+fn test_quote_fee_capped() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    // a minimum fee above 10% of the deposit is capped at 10%
+    stream_client.set_asset_fee(
+        &settings.admin,
+        &token_address,
+        &FeeModel::ClampedBps(1, 1_000_000_000, 1_000_000_000),
+    );
+    assert_eq!(stream_client.quote_fee(&token_address, &10_000), 1_000);
+    assert_eq!(stream_client.quote_fee(&token_address, &9), 0);
+
+    // as is a flat fee
+    stream_client.set_asset_fee(&settings.admin, &token_address, &FeeModel::Flat(5_000));
+    assert_eq!(stream_client.quote_fee(&token_address, &10_000), 1_000);
+    assert_eq!(stream_client.quote_fee(&token_address, &10_000_000), 5_000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #204)")]
+fn test_set_asset_fee_exceeds_max_bps() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    stream_client.set_asset_fee(&settings.admin, &token_address, &FeeModel::Bps(1_001));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #205)")]
+fn test_set_asset_fee_invalid_clamp() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    stream_client.set_asset_fee(
+        &settings.admin,
+        &token_address,
+        &FeeModel::ClampedBps(25, 10_000, 100),
+    );
+}
+
+#[test]
+fn test_asset_fee_outlives_temporary_ttl() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    stream_client.set_asset_fee(&settings.admin, &token_address, &FeeModel::Flat(5_000));
+
+    // jump past the ~120 day TTL a temporary entry is bumped to, creating streams
+    // along the way to keep the stream contract instance live
+    for _ in 0..5 {
+        e.jump(30 * ONE_DAY_LEDGERS);
+
+        token_client.mint(&yosemite, &10_000_000);
+        let stream_id = stream_client.create_range(
+            &yosemite,
+            &everest,
+            &1_000_000,
+            &token_address,
+            &e.ledger().timestamp(),
+            &(e.ledger().timestamp() + 1_000),
+            &true,
+            &e.ledger().timestamp(),
+            &0,
+            &false,
+        );
+        assert_eq!(stream_client.get_stream(&stream_id).unwrap().fee, 5_000);
+    }
+    assert_eq!(
+        stream_client.asset_fee(&token_address),
+        Some(FeeModel::Flat(5_000))
+    );
+}
+
+#[test]
+fn test_migrate_asset_fee() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    // write a fee the way previous versions of the contract stored it
+    e.as_contract(&stream_address, || {
+        let key = StreamDataKey::AssetFee(token_address.clone());
+        e.storage()
+            .temporary()
+            .set::<StreamDataKey, i128>(&key, &5_000);
+    });
+
+    stream_client.migrate_asset_fee(&settings.admin, &token_address);
+
+    // verify auths
+    assert_eq!(
+        e.auths()[0],
+        (
+            settings.admin.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    stream_address.clone(),
+                    Symbol::new(&e, "migrate_asset_fee"),
+                    vec![&e, settings.admin.to_val(), token_address.to_val()]
+                )),
+                sub_invocations: std::vec![]
+            }
+        )
+    );
+
+    // verify chain results
+    assert_eq!(
+        stream_client.asset_fee(&token_address),
+        Some(FeeModel::Flat(5_000))
+    );
+    e.as_contract(&stream_address, || {
+        let key = StreamDataKey::AssetFee(token_address.clone());
+        assert!(!e.storage().temporary().has(&key));
+    });
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #202)")]
+fn test_migrate_asset_fee_not_set() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    stream_client.migrate_asset_fee(&settings.admin, &token_address);
+}
+
+#[test]
+fn test_legacy_asset_fee_migrated_on_read() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+    let now = e.ledger().timestamp();
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &100_000_000);
+
+    // write a fee the way previous versions of the contract stored it
+    e.as_contract(&stream_address, || {
+        let key = StreamDataKey::AssetFee(token_address.clone());
+        e.storage()
+            .temporary()
+            .set::<StreamDataKey, i128>(&key, &5_000);
+    });
+
+    // streams are charged the legacy fee instead of the base fee
+    let stream_id = stream_client.create_range(
+        &yosemite,
+        &everest,
+        &10_000_000,
+        &token_address,
+        &now,
+        &(now + 10_000),
+        &true,
+        &now,
+        &0,
+        &false,
+    );
+    assert_eq!(stream_client.get_stream(&stream_id).unwrap().fee, 5_000);
+    assert_eq!(token_client.balance(&yosemite), 100_000_000 - 10_005_000);
+
+    // and the fee is moved to persistent storage
+    e.as_contract(&stream_address, || {
+        let key = StreamDataKey::AssetFee(token_address.clone());
+        assert!(!e.storage().temporary().has(&key));
+        assert_eq!(
+            e.storage()
+                .persistent()
+                .get::<StreamDataKey, FeeModel>(&key),
+            Some(FeeModel::Flat(5_000))
+        );
+    });
+}
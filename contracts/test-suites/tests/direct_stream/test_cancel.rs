@@ -8,7 +8,7 @@ use test_suites::{
     direct_stream::{create_direct_stream, default_stream_settings},
     env::EnvTestUtils, ONE_DAY_LEDGERS,
 };
-use zentra_direct_stream::DirectStreamContractClient;
+use zentra_direct_stream::{types::Status, DirectStreamContractClient};
 
 #[test]
 fn test_cancel() {
@@ -38,9 +38,12 @@ fn test_cancel() {
         &deadline,
         &true,
         &e.ledger().timestamp(),
+        &0,
+        &false,
     );
     e.jump(9 * ONE_DAY_LEDGERS);
-    
+
+    let streamed_amount = stream_client.streamed_amount(&stream_id);
     stream_client.cancel(&yosemite, &stream_id);
  
     // verify auths
@@ -61,5 +64,130 @@ fn test_cancel() {
 
     // verify chain results
     let stream = stream_client.get_stream(&stream_id).unwrap();
-    assert_eq!(stream.is_cancelled, true);
+    assert!(stream.is_cancelled);
+    assert!(!stream.is_depleted);
+    assert_eq!(stream.refunded, 10_000_000 - streamed_amount);
+    assert_eq!(
+        token_client.balance(&yosemite),
+        100_000_000 - 1_000 - streamed_amount
+    );
+    assert_eq!(token_client.balance(&everest), 100_000_000);
+    assert_eq!(stream_client.status(&stream_id), Some(Status::Cancelled));
+
+    // the streamed amount is frozen at the time of cancellation
+    e.jump(9 * ONE_DAY_LEDGERS);
+    assert_eq!(stream_client.streamed_amount(&stream_id), streamed_amount);
+
+    // the recipient can still withdraw what was streamed before the cancellation
+    stream_client.withdraw(&everest, &everest, &stream_id, &streamed_amount);
+    let stream = stream_client.get_stream(&stream_id).unwrap();
+    assert_eq!(stream.withdrawn, streamed_amount);
+    assert!(stream.is_depleted);
+    assert_eq!(stream_client.status(&stream_id), Some(Status::Depleted));
+    assert_eq!(token_client.balance(&everest), 100_000_000 + streamed_amount);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #207)")]
+fn test_cancel_already_cancelled() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+    let deadline = e.ledger().timestamp() + (365 * ONE_DAY_LEDGERS) as u64;
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &100_000_000);
+
+    let stream_id = stream_client.create_range(
+        &yosemite,
+        &everest,
+        &10_000_000,
+        &token_address,
+        &e.ledger().timestamp(),
+        &deadline,
+        &true,
+        &e.ledger().timestamp(),
+        &0,
+        &false,
+    );
+    e.jump(9 * ONE_DAY_LEDGERS);
+
+    stream_client.cancel(&yosemite, &stream_id);
+    stream_client.cancel(&everest, &stream_id);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #206)")]
+fn test_cancel_not_cancellable() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+    let deadline = e.ledger().timestamp() + (365 * ONE_DAY_LEDGERS) as u64;
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &100_000_000);
+
+    // create a non-cancellable direct range stream
+    let stream_id = stream_client.create_range(
+        &yosemite,
+        &everest,
+        &10_000_000,
+        &token_address,
+        &e.ledger().timestamp(),
+        &deadline,
+        &false,
+        &e.ledger().timestamp(),
+        &0,
+        &false,
+    );
+    e.jump(9 * ONE_DAY_LEDGERS);
+
+    stream_client.cancel(&yosemite, &stream_id);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #213)")]
+fn test_cancel_not_sender_or_recipient() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+    let deadline = e.ledger().timestamp() + (365 * ONE_DAY_LEDGERS) as u64;
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let denali = Address::generate(&e);
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &100_000_000);
+
+    let stream_id = stream_client.create_range(
+        &yosemite,
+        &everest,
+        &10_000_000,
+        &token_address,
+        &e.ledger().timestamp(),
+        &deadline,
+        &true,
+        &e.ledger().timestamp(),
+        &0,
+        &false,
+    );
+    e.jump(9 * ONE_DAY_LEDGERS);
+
+    stream_client.cancel(&denali, &stream_id);
 }
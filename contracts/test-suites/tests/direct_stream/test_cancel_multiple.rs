@@ -0,0 +1,202 @@
+#[cfg(test)]
+use sep_41_token::testutils::MockTokenClient;
+use soroban_sdk::{testutils::Address as _, vec, Address, Env, Error, Vec};
+use test_suites::{
+    common::{count_events, create_stellar_token},
+    direct_stream::{create_direct_stream, default_stream_settings},
+    env::EnvTestUtils,
+};
+use zentra_direct_stream::{types::Status, DirectStreamContractClient};
+
+#[test]
+fn test_cancel_multiple() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+    let now = e.ledger().timestamp();
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let denali = Address::generate(&e);
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let (other_token_address, _) = create_stellar_token(&e, &settings.admin);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let other_token_client = MockTokenClient::new(&e, &other_token_address);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &100_000_000);
+    other_token_client.mint(&yosemite, &100_000_000);
+
+    let mut stream_ids = Vec::new(&e);
+    for (recipient, token_address) in [
+        (&everest, &token_address),
+        (&denali, &token_address),
+        (&everest, &other_token_address),
+    ] {
+        stream_ids.push_back(stream_client.create_range(
+            &yosemite,
+            recipient,
+            &10_000_000,
+            token_address,
+            &now,
+            &(now + 10_000),
+            &true,
+            &now,
+            &0,
+            &false,
+        ));
+    }
+    let balance = token_client.balance(&yosemite);
+    let other_balance = other_token_client.balance(&yosemite);
+
+    // each stream is 10% streamed
+    e.jump(200);
+    let transfers = count_events(&e, &token_address, "transfer");
+    let other_transfers = count_events(&e, &other_token_address, "transfer");
+    let refunds = stream_client.cancel_multiple(&yosemite, &stream_ids, &false);
+
+    // verify chain results
+    assert_eq!(refunds, vec![&e, 9_000_000, 9_000_000, 9_000_000]);
+    assert_eq!(token_client.balance(&yosemite), balance + 18_000_000);
+    assert_eq!(
+        other_token_client.balance(&yosemite),
+        other_balance + 9_000_000
+    );
+    for stream_id in stream_ids.iter() {
+        assert_eq!(stream_client.status(&stream_id), Some(Status::Cancelled));
+        assert_eq!(stream_client.withdrawable_amount(&stream_id), 1_000_000);
+    }
+
+    // one cancellation event per stream, and one refund per token
+    assert_eq!(count_events(&e, &stream_address, "stream_cancelled"), 3);
+    assert_eq!(count_events(&e, &token_address, "transfer"), transfers + 1);
+    assert_eq!(
+        count_events(&e, &other_token_address, "transfer"),
+        other_transfers + 1
+    );
+}
+
+#[test]
+fn test_cancel_multiple_settled_streams() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+    let now = e.ledger().timestamp();
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &100_000_000);
+
+    let mut stream_ids = Vec::new(&e);
+    for _ in 0..2 {
+        stream_ids.push_back(stream_client.create_range(
+            &yosemite,
+            &everest,
+            &10_000_000,
+            &token_address,
+            &now,
+            &(now + 10_000),
+            &true,
+            &now,
+            &0,
+            &false,
+        ));
+    }
+    e.jump(200);
+    stream_client.cancel(&yosemite, &stream_ids.get_unchecked(0));
+    let balance = token_client.balance(&yosemite);
+
+    // settled streams fail the batch unless skipped
+    let result = stream_client.try_cancel_multiple(&yosemite, &stream_ids, &false);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(207))));
+    assert_eq!(
+        stream_client.status(&stream_ids.get_unchecked(1)),
+        Some(Status::Active)
+    );
+
+    let refunds = stream_client.cancel_multiple(&yosemite, &stream_ids, &true);
+    assert_eq!(refunds, vec![&e, 0, 9_000_000]);
+    assert_eq!(token_client.balance(&yosemite), balance + 9_000_000);
+    assert_eq!(
+        stream_client.status(&stream_ids.get_unchecked(1)),
+        Some(Status::Cancelled)
+    );
+}
+
+#[test]
+fn test_cancel_multiple_validation() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+    let now = e.ledger().timestamp();
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let denali = Address::generate(&e);
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &100_000_000);
+
+    let mut stream_ids = Vec::new(&e);
+    for cancellable in [true, false] {
+        stream_ids.push_back(stream_client.create_range(
+            &yosemite,
+            &everest,
+            &10_000_000,
+            &token_address,
+            &now,
+            &(now + 10_000),
+            &cancellable,
+            &now,
+            &0,
+            &false,
+        ));
+    }
+
+    let result = stream_client.try_cancel_multiple(&yosemite, &Vec::new(&e), &true);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(227))));
+
+    let result = stream_client.try_cancel_multiple(&yosemite, &stream_ids, &true);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(206))));
+
+    let result = stream_client.try_cancel_multiple(&denali, &stream_ids, &true);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(213))));
+
+    let result = stream_client.try_cancel_multiple(&yosemite, &vec![&e, 0, 2], &true);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(200))));
+
+    // each stream refunds a different sender, so 13 streams write 13 streams, 13 sender
+    // balances and the contract's balance, more than the 25 writes allowed
+    let mut stream_ids = Vec::new(&e);
+    for _ in 0..13 {
+        let sender = Address::generate(&e);
+        token_client.mint(&sender, &10_001_000);
+        stream_ids.push_back(stream_client.create_range(
+            &sender,
+            &everest,
+            &10_000_000,
+            &token_address,
+            &now,
+            &(now + 10_000),
+            &true,
+            &now,
+            &0,
+            &false,
+        ));
+    }
+    let result = stream_client.try_cancel_multiple(&everest, &stream_ids, &false);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(227))));
+
+    // while 12 fill them exactly
+    let refunds = stream_client.cancel_multiple(&everest, &stream_ids.slice(0..12), &false);
+    assert_eq!(refunds.len(), 12);
+}
@@ -0,0 +1,118 @@
+#[cfg(test)]
+use sep_41_token::testutils::MockTokenClient;
+use soroban_sdk::{
+    testutils::{Address as _, AuthorizedFunction, AuthorizedInvocation},
+    vec, Address, Env, IntoVal, Symbol,
+};
+use test_suites::{
+    direct_stream::{create_direct_stream, default_stream_settings},
+    env::EnvTestUtils,
+    ONE_DAY_LEDGERS,
+};
+use zentra_direct_stream::DirectStreamContractClient;
+
+#[test]
+fn test_claim_fees() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+    let deadline = e.ledger().timestamp() + (365 * ONE_DAY_LEDGERS) as u64;
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let treasury = Address::generate(&e);
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &100_000_000);
+    let base_fee = stream_client.quote_fee(&token_address, &10_000_000);
+
+    // create two streams, each charged the base fee
+    for _ in 0..2 {
+        stream_client.create_range(
+            &yosemite,
+            &everest,
+            &10_000_000,
+            &token_address,
+            &e.ledger().timestamp(),
+            &deadline,
+            &true,
+            &e.ledger().timestamp(),
+            &0,
+            &false,
+        );
+    }
+    assert_eq!(stream_client.accrued_fees(&token_address), 2 * base_fee);
+
+    let claim_amount = base_fee + 1;
+    stream_client.claim_fees(&token_address, &treasury, &claim_amount);
+
+    // verify auths
+    assert_eq!(
+        e.auths()[0],
+        (
+            settings.admin.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    stream_address.clone(),
+                    Symbol::new(&e, "claim_fees"),
+                    vec![
+                        &e,
+                        token_address.to_val(),
+                        treasury.to_val(),
+                        claim_amount.into_val(&e)
+                    ]
+                )),
+                sub_invocations: std::vec![]
+            }
+        )
+    );
+
+    // verify chain results
+    assert_eq!(token_client.balance(&treasury), claim_amount);
+    assert_eq!(
+        stream_client.accrued_fees(&token_address),
+        2 * base_fee - claim_amount
+    );
+    assert_eq!(
+        token_client.balance(&stream_address),
+        2 * 10_000_000 + 2 * base_fee - claim_amount
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #203)")]
+fn test_claim_fees_exceeds_accrued() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+    let deadline = e.ledger().timestamp() + (365 * ONE_DAY_LEDGERS) as u64;
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &100_000_000);
+    let base_fee = stream_client.quote_fee(&token_address, &10_000_000);
+
+    stream_client.create_range(
+        &yosemite,
+        &everest,
+        &10_000_000,
+        &token_address,
+        &e.ledger().timestamp(),
+        &deadline,
+        &true,
+        &e.ledger().timestamp(),
+        &0,
+        &false,
+    );
+
+    // the contract holds the stream deposit, but only the fee can be claimed
+    stream_client.claim_fees(&token_address, &settings.admin, &(base_fee + 1));
+}
@@ -0,0 +1,106 @@
+#[cfg(test)]
+use sep_41_token::testutils::MockTokenClient;
+use soroban_sdk::{testutils::Address as _, Address, Env, Error};
+use test_suites::{
+    direct_stream::{create_direct_stream, default_stream_settings},
+    env::EnvTestUtils,
+    ONE_DAY_LEDGERS,
+};
+use zentra_direct_stream::{types::Status, DirectStreamContractClient};
+
+#[test]
+fn test_cliff_amount() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+    let now = e.ledger().timestamp();
+    // jumps advance 5 seconds per ledger
+    let duration = (40 * ONE_DAY_LEDGERS * 5) as u64;
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &100_000_000);
+
+    // 2_000_000 unlocks at the cliff, 10% of the way through the stream
+    let stream_id = stream_client.create_range(
+        &yosemite,
+        &everest,
+        &10_000_000,
+        &token_address,
+        &now,
+        &(now + duration),
+        &true,
+        &(now + duration / 10),
+        &2_000_000,
+        &false,
+    );
+    assert_eq!(
+        stream_client.get_stream(&stream_id).unwrap().cliff_amount,
+        2_000_000
+    );
+
+    // nothing is streamed before the cliff
+    e.jump(2 * ONE_DAY_LEDGERS);
+    assert_eq!(stream_client.status(&stream_id), Some(Status::Cliff));
+    assert_eq!(stream_client.streamed_amount(&stream_id), 0);
+
+    // the cliff amount unlocks along with the amount accrued since the start
+    e.jump(2 * ONE_DAY_LEDGERS);
+    assert_eq!(stream_client.status(&stream_id), Some(Status::Active));
+    assert_eq!(
+        stream_client.streamed_amount(&stream_id),
+        2_000_000 + 800_000
+    );
+
+    // the remainder streams linearly to the stop time
+    e.jump(16 * ONE_DAY_LEDGERS);
+    assert_eq!(
+        stream_client.streamed_amount(&stream_id),
+        2_000_000 + 4_000_000
+    );
+
+    stream_client.withdraw(&everest, &everest, &stream_id, &6_000_000);
+    assert_eq!(token_client.balance(&everest), 6_000_000);
+
+    e.jump(20 * ONE_DAY_LEDGERS);
+    assert_eq!(stream_client.status(&stream_id), Some(Status::Completed));
+    assert_eq!(stream_client.streamed_amount(&stream_id), 10_000_000);
+}
+
+#[test]
+fn test_cliff_amount_invalid() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+    let now = e.ledger().timestamp();
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &100_000_000);
+
+    for cliff_amount in [-1, 10_000_001] {
+        let result = stream_client.try_create_range(
+            &yosemite,
+            &everest,
+            &10_000_000,
+            &token_address,
+            &now,
+            &(now + 1_000),
+            &true,
+            &(now + 100),
+            &cliff_amount,
+            &false,
+        );
+        assert_eq!(result.err(), Some(Ok(Error::from_contract_error(216))));
+    }
+}
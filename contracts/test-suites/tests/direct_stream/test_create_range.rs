@@ -2,7 +2,7 @@
 use sep_41_token::testutils::MockTokenClient;
 use soroban_sdk::{
     testutils::{Address as _, AuthorizedFunction, AuthorizedInvocation},
-    vec, Address, Env, Symbol, IntoVal,
+    vec, Address, Env, Error, IntoVal, Symbol,
 };
 use test_suites::{
     direct_stream::{create_direct_stream, default_stream_settings},
@@ -29,6 +29,7 @@ fn test_create_range() {
     token_client.mint(&everest, &100_000_000);
 
     let stream_amount = 10_000_000_i128;
+    let fee = stream_client.quote_fee(&token_address, &stream_amount);
 
     // create a direct range stream
     let stream_id = stream_client.create_range(
@@ -40,6 +41,8 @@ fn test_create_range() {
         &deadline,
         &true,
         &e.ledger().timestamp(),
+        &0,
+        &false,
     );
 
     // verify auths
@@ -60,7 +63,9 @@ fn test_create_range() {
                         e.ledger().timestamp().into_val(&e),
                         deadline.into_val(&e),
                         true.into_val(&e),
-                        e.ledger().timestamp().into_val(&e)
+                        e.ledger().timestamp().into_val(&e),
+                        0_i128.into_val(&e),
+                        false.into_val(&e)
                     ]
                 )),
                 sub_invocations: std::vec![AuthorizedInvocation {
@@ -71,7 +76,7 @@ fn test_create_range() {
                             &e,
                             yosemite.to_val(),
                             stream_address.to_val(),
-                            stream_amount.into_val(&e)
+                            (stream_amount + fee).into_val(&e)
                         ]
                     )),
                     sub_invocations: std::vec![]
@@ -83,10 +88,59 @@ fn test_create_range() {
     // verify chain results
     let stream = stream_client.get_stream(&stream_id).unwrap();
     assert_eq!(stream.deposit, 10_000_000);
+    assert_eq!(stream.fee, 1_000);
     assert_eq!(stream.stop_time, deadline);
     assert_eq!(stream.withdrawn, 0);
-    assert_eq!(stream.is_cancelled, false);
-    assert_eq!(stream.is_cancellable, true);
-    assert_eq!(stream.is_depleted, false);
+    assert!(!stream.is_cancelled);
+    assert!(stream.is_cancellable);
+    assert!(!stream.is_depleted);
     assert_eq!(stream.refunded, 0);
+    assert_eq!(
+        token_client.balance(&yosemite),
+        100_000_000 - stream_amount - fee
+    );
+}
+
+#[test]
+fn test_create_range_validation() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+    let now = e.ledger().timestamp();
+    let deadline = now + (365 * ONE_DAY_LEDGERS) as u64;
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &100_000_000);
+
+    let cases = [
+        // (amount, start_time, stop_time, cliff_time, error code)
+        (0, now, deadline, now, 208),
+        (-1, now, deadline, now, 208),
+        (10_000_000, now - 1, deadline, now, 209),
+        (10_000_000, now + 10, deadline, now + 9, 210),
+        (10_000_000, now, now, now, 211),
+        (10_000_000, now + 10, now + 5, now + 10, 211),
+        (10_000_000, now, now + 10, now + 10, 212),
+    ];
+    for (amount, start_time, stop_time, cliff_time, code) in cases {
+        let result = stream_client.try_create_range(
+            &yosemite,
+            &everest,
+            &amount,
+            &token_address,
+            &start_time,
+            &stop_time,
+            &true,
+            &cliff_time,
+            &0,
+            &false,
+        );
+        assert_eq!(result.err(), Some(Ok(Error::from_contract_error(code))));
+    }
 }
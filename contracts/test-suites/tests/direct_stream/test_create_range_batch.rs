@@ -0,0 +1,137 @@
+#[cfg(test)]
+use sep_41_token::testutils::MockTokenClient;
+use soroban_sdk::{
+    testutils::{Address as _, AuthorizedFunction, AuthorizedInvocation},
+    vec, Address, Env, Error, IntoVal, Symbol, Vec,
+};
+use test_suites::{
+    direct_stream::{create_direct_stream, default_stream_settings},
+    env::EnvTestUtils,
+};
+use zentra_direct_stream::{types::RangeParams, DirectStreamContractClient};
+
+fn range_params(recipient: &Address, amount: i128, start_time: u64) -> RangeParams {
+    RangeParams {
+        recipient: recipient.clone(),
+        amount,
+        start_time,
+        stop_time: start_time + 10_000,
+        cliff_time: start_time,
+        cliff_amount: 0,
+        cancellable: true,
+        transferable: false,
+    }
+}
+
+#[test]
+fn test_create_range_batch() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+    let now = e.ledger().timestamp();
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let denali = Address::generate(&e);
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &100_000_000);
+
+    let params = vec![
+        &e,
+        range_params(&everest, 10_000_000, now),
+        range_params(&denali, 20_000_000, now),
+        range_params(&everest, 5_000_000, now + 1_000),
+    ];
+    let stream_ids = stream_client.create_range_batch(&yosemite, &token_address, &params);
+
+    // verify auths
+    assert_eq!(
+        e.auths()[0],
+        (
+            yosemite.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    stream_address.clone(),
+                    Symbol::new(&e, "create_range_batch"),
+                    vec![
+                        &e,
+                        yosemite.to_val(),
+                        token_address.to_val(),
+                        params.into_val(&e)
+                    ]
+                )),
+                sub_invocations: std::vec![AuthorizedInvocation {
+                    function: AuthorizedFunction::Contract((
+                        token_address.clone(),
+                        Symbol::new(&e, "transfer"),
+                        vec![
+                            &e,
+                            yosemite.to_val(),
+                            stream_address.to_val(),
+                            (35_000_000_i128 + 3_000).into_val(&e)
+                        ]
+                    )),
+                    sub_invocations: std::vec![]
+                }]
+            }
+        )
+    );
+
+    // verify chain results
+    assert_eq!(stream_ids, vec![&e, 1, 2, 3]);
+    for (stream_id, stream_params) in stream_ids.iter().zip(params.iter()) {
+        let stream = stream_client.get_stream(&stream_id).unwrap();
+        assert_eq!(stream.sender, yosemite);
+        assert_eq!(stream.recipient, stream_params.recipient);
+        assert_eq!(stream.deposit, stream_params.amount);
+        assert_eq!(stream.start_time, stream_params.start_time);
+        assert_eq!(stream.fee, 1_000);
+    }
+    assert_eq!(token_client.balance(&stream_address), 35_000_000 + 3_000);
+    assert_eq!(stream_client.accrued_fees(&token_address), 3_000);
+}
+
+#[test]
+fn test_create_range_batch_is_atomic() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+    let now = e.ledger().timestamp();
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &100_000_000);
+
+    let mut invalid_params = range_params(&everest, 10_000_000, now);
+    invalid_params.stop_time = now;
+    let params = vec![
+        &e,
+        range_params(&everest, 10_000_000, now),
+        invalid_params,
+        range_params(&everest, 10_000_000, now),
+    ];
+
+    // the error code carries the index of the invalid params and the underlying error
+    let result = stream_client.try_create_range_batch(&yosemite, &token_address, &params);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(2_211))));
+
+    // no streams are created
+    assert!(stream_client.get_stream(&1).is_none());
+    assert_eq!(token_client.balance(&yosemite), 100_000_000);
+
+    let params = vec![&e, range_params(&everest, 0, now)];
+    let result = stream_client.try_create_range_batch(&yosemite, &token_address, &params);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(1_208))));
+
+    let result = stream_client.try_create_range_batch(&yosemite, &token_address, &Vec::new(&e));
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(227))));
+}
@@ -0,0 +1,135 @@
+#[cfg(test)]
+use sep_41_token::testutils::MockTokenClient;
+use soroban_sdk::{
+    testutils::{Address as _, Events},
+    vec, Address, Env, Error, IntoVal, Symbol,
+};
+use test_suites::{
+    direct_stream::{create_direct_stream, default_stream_settings},
+    env::EnvTestUtils,
+};
+use zentra_direct_stream::DirectStreamContractClient;
+
+#[test]
+fn test_operator_withdraw() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+    let now = e.ledger().timestamp();
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let denali = Address::generate(&e);
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &100_000_000);
+
+    let stream_id = stream_client.create_range(
+        &yosemite,
+        &everest,
+        &10_000_000,
+        &token_address,
+        &now,
+        &(now + 10_000),
+        &true,
+        &now,
+        &0,
+        &false,
+    );
+
+    let expiry = now + 2_000;
+    stream_client.approve_operator(&everest, &denali, &expiry);
+    let events = e.events().all();
+    assert_eq!(
+        events.slice(events.len() - 1..),
+        vec![
+            &e,
+            (
+                stream_address.clone(),
+                (
+                    Symbol::new(&e, "operator_approved"),
+                    everest.clone(),
+                    denali.clone()
+                )
+                    .into_val(&e),
+                expiry.into_val(&e)
+            )
+        ]
+    );
+    assert!(stream_client.is_operator(&everest, &denali));
+
+    // the operator can withdraw, but only to the recipient
+    e.jump(200);
+    let result = stream_client.try_withdraw_max(&denali, &stream_id, &denali);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(214))));
+    stream_client.withdraw_max(&denali, &stream_id, &everest);
+    assert_eq!(token_client.balance(&everest), 1_000_000);
+
+    // the approval lapses at the expiry
+    e.jump(200);
+    assert!(!stream_client.is_operator(&everest, &denali));
+    let result = stream_client.try_withdraw_max(&denali, &stream_id, &everest);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(213))));
+}
+
+#[test]
+fn test_revoke_operator() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+    let now = e.ledger().timestamp();
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let denali = Address::generate(&e);
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &100_000_000);
+
+    let stream_id = stream_client.create_range(
+        &yosemite,
+        &everest,
+        &10_000_000,
+        &token_address,
+        &now,
+        &(now + 10_000),
+        &true,
+        &now,
+        &0,
+        &false,
+    );
+
+    let result = stream_client.try_approve_operator(&everest, &denali, &now);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(232))));
+
+    stream_client.approve_operator(&everest, &denali, &(now + 10_000));
+    stream_client.revoke_operator(&everest, &denali);
+    let events = e.events().all();
+    assert_eq!(
+        events.slice(events.len() - 1..),
+        vec![
+            &e,
+            (
+                stream_address.clone(),
+                (
+                    Symbol::new(&e, "operator_revoked"),
+                    everest.clone(),
+                    denali.clone()
+                )
+                    .into_val(&e),
+                ().into_val(&e)
+            )
+        ]
+    );
+    assert!(!stream_client.is_operator(&everest, &denali));
+
+    e.jump(200);
+    let result = stream_client.try_withdraw_max(&denali, &stream_id, &everest);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(213))));
+}
@@ -0,0 +1,241 @@
+#[cfg(test)]
+use sep_41_token::testutils::MockTokenClient;
+use soroban_sdk::{
+    testutils::{Address as _, Events},
+    vec, Address, Env, Error, IntoVal, Symbol,
+};
+use test_suites::{
+    direct_stream::{create_direct_stream, default_stream_settings},
+    env::EnvTestUtils,
+};
+use zentra_direct_stream::{
+    types::{Status, Tranche},
+    DirectStreamContractClient,
+};
+
+#[test]
+fn test_pause_and_resume() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+    let now = e.ledger().timestamp();
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &100_000_000);
+
+    let stream_id = stream_client.create_range(
+        &yosemite,
+        &everest,
+        &10_000_000,
+        &token_address,
+        &now,
+        &(now + 10_000),
+        &true,
+        &now,
+        &0,
+        &false,
+    );
+
+    // each jump of 200 ledgers advances 1_000 seconds
+    e.jump(200);
+    stream_client.pause(&stream_id);
+    let events = e.events().all();
+    assert_eq!(
+        events.slice(events.len() - 1..),
+        vec![
+            &e,
+            (
+                stream_address.clone(),
+                (Symbol::new(&e, "stream_paused"), stream_id).into_val(&e),
+                ().into_val(&e)
+            )
+        ]
+    );
+
+    // accrual is frozen while paused, but the streamed amount can be withdrawn
+    e.jump(400);
+    assert_eq!(stream_client.status(&stream_id), Some(Status::Paused));
+    assert_eq!(stream_client.streamed_amount(&stream_id), 1_000_000);
+    stream_client.withdraw_max(&everest, &stream_id, &everest);
+    assert_eq!(token_client.balance(&everest), 1_000_000);
+
+    stream_client.resume(&stream_id);
+    let events = e.events().all();
+    assert_eq!(
+        events.slice(events.len() - 1..),
+        vec![
+            &e,
+            (
+                stream_address.clone(),
+                (Symbol::new(&e, "stream_resumed"), stream_id).into_val(&e),
+                2_000_u64.into_val(&e)
+            )
+        ]
+    );
+
+    // the remaining schedule is shifted forward by the paused duration
+    let stream = stream_client.get_stream(&stream_id).unwrap();
+    assert_eq!(stream.start_time, now + 2_000);
+    assert_eq!(stream.stop_time, now + 12_000);
+    assert_eq!(stream_client.status(&stream_id), Some(Status::Active));
+    assert_eq!(stream_client.streamed_amount(&stream_id), 1_000_000);
+
+    e.jump(200);
+    assert_eq!(stream_client.streamed_amount(&stream_id), 2_000_000);
+
+    e.jump(1_600);
+    assert_eq!(stream_client.streamed_amount(&stream_id), 10_000_000);
+    assert_eq!(stream_client.status(&stream_id), Some(Status::Completed));
+}
+
+#[test]
+fn test_pause_shifts_tranches() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+    let now = e.ledger().timestamp();
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &100_000_000);
+
+    let tranches = vec![
+        &e,
+        Tranche {
+            amount: 4_000_000,
+            unlock_time: now + 1_000,
+        },
+        Tranche {
+            amount: 6_000_000,
+            unlock_time: now + 2_000,
+        },
+    ];
+    let stream_id = stream_client.create_with_tranches(
+        &yosemite,
+        &everest,
+        &10_000_000,
+        &token_address,
+        &now,
+        &tranches,
+        &true,
+    );
+
+    e.jump(100);
+    stream_client.pause(&stream_id);
+    e.jump(200);
+    stream_client.resume(&stream_id);
+
+    // the first tranche now unlocks 1_000 seconds later
+    e.jump(99);
+    assert_eq!(stream_client.streamed_amount(&stream_id), 0);
+    e.jump(1);
+    assert_eq!(stream_client.streamed_amount(&stream_id), 4_000_000);
+    assert_eq!(
+        stream_client.get_stream(&stream_id).unwrap().stop_time,
+        now + 3_000
+    );
+}
+
+#[test]
+fn test_pause_validation() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+    let now = e.ledger().timestamp();
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &100_000_000);
+
+    let stream_id = stream_client.create_range(
+        &yosemite,
+        &everest,
+        &10_000_000,
+        &token_address,
+        &now,
+        &(now + 10_000),
+        &true,
+        &now,
+        &0,
+        &false,
+    );
+
+    let result = stream_client.try_resume(&stream_id);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(224))));
+
+    stream_client.pause(&stream_id);
+    let result = stream_client.try_pause(&stream_id);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(223))));
+
+    // a cancelled stream can't be paused or resumed
+    stream_client.cancel(&yosemite, &stream_id);
+    let result = stream_client.try_resume(&stream_id);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(224))));
+
+    // nor can a fully streamed one
+    let stream_id = stream_client.create_range(
+        &yosemite,
+        &everest,
+        &10_000_000,
+        &token_address,
+        &now,
+        &(now + 10_000),
+        &true,
+        &now,
+        &0,
+        &false,
+    );
+    e.jump(2_000);
+    let result = stream_client.try_pause(&stream_id);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(207))));
+
+    // a non-cancellable stream can't be paused
+    let now = e.ledger().timestamp();
+    let stream_id = stream_client.create_range(
+        &yosemite,
+        &everest,
+        &10_000_000,
+        &token_address,
+        &now,
+        &(now + 100_000),
+        &false,
+        &now,
+        &0,
+        &false,
+    );
+    let result = stream_client.try_pause(&stream_id);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(206))));
+
+    // nor one that has been renounced
+    let stream_id = stream_client.create_range(
+        &yosemite,
+        &everest,
+        &10_000_000,
+        &token_address,
+        &now,
+        &(now + 100_000),
+        &true,
+        &now,
+        &0,
+        &false,
+    );
+    stream_client.renounce(&stream_id);
+    let result = stream_client.try_pause(&stream_id);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(206))));
+}
@@ -0,0 +1,101 @@
+#[cfg(test)]
+use sep_41_token::testutils::MockTokenClient;
+use soroban_sdk::{
+    testutils::{Address as _, AuthorizedFunction, AuthorizedInvocation},
+    vec, Address, Env, Error, Symbol, TryIntoVal,
+};
+use test_suites::{
+    direct_stream::{create_direct_stream, default_stream_settings},
+    env::EnvTestUtils,
+    ONE_DAY_LEDGERS,
+};
+use zentra_direct_stream::DirectStreamContractClient;
+
+#[test]
+fn test_renounce() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+    let deadline = e.ledger().timestamp() + (365 * ONE_DAY_LEDGERS) as u64;
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &100_000_000);
+
+    // create a direct range stream
+    let stream_id = stream_client.create_range(
+        &yosemite,
+        &everest,
+        &10_000_000,
+        &token_address,
+        &e.ledger().timestamp(),
+        &deadline,
+        &true,
+        &e.ledger().timestamp(),
+        &0,
+        &false,
+    );
+
+    stream_client.renounce(&stream_id);
+
+    // verify auths
+    assert_eq!(
+        e.auths()[0],
+        (
+            yosemite.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    stream_address.clone(),
+                    Symbol::new(&e, "renounce"),
+                    vec![&e, stream_id.try_into_val(&e).unwrap()]
+                )),
+                sub_invocations: std::vec![]
+            }
+        )
+    );
+
+    // verify chain results
+    let stream = stream_client.get_stream(&stream_id).unwrap();
+    assert!(!stream.is_cancellable);
+
+    let result = stream_client.try_cancel(&yosemite, &stream_id);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(206))));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #206)")]
+fn test_renounce_not_cancellable() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+    let deadline = e.ledger().timestamp() + (365 * ONE_DAY_LEDGERS) as u64;
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &100_000_000);
+
+    let stream_id = stream_client.create_range(
+        &yosemite,
+        &everest,
+        &10_000_000,
+        &token_address,
+        &e.ledger().timestamp(),
+        &deadline,
+        &false,
+        &e.ledger().timestamp(),
+        &0,
+        &false,
+    );
+
+    stream_client.renounce(&stream_id);
+}
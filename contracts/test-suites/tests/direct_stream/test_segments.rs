@@ -0,0 +1,167 @@
+#[cfg(test)]
+use sep_41_token::testutils::MockTokenClient;
+use soroban_sdk::{testutils::Address as _, vec, Address, Env, Error, Vec};
+use test_suites::{
+    direct_stream::{create_direct_stream, default_stream_settings},
+    env::EnvTestUtils,
+};
+use zentra_direct_stream::{
+    types::{Segment, SegmentExponent, Status, StreamKind},
+    DirectStreamContractClient,
+};
+
+#[test]
+fn test_create_with_segments() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+    let now = e.ledger().timestamp();
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &100_000_000);
+
+    let segments = vec![
+        &e,
+        Segment {
+            amount: 1_000_000,
+            exponent: SegmentExponent::Linear,
+            end_time: now + 1_000,
+        },
+        Segment {
+            amount: 4_000_000,
+            exponent: SegmentExponent::Convex(2),
+            end_time: now + 2_000,
+        },
+        Segment {
+            amount: 5_000_000,
+            exponent: SegmentExponent::Concave(2),
+            end_time: now + 3_000,
+        },
+    ];
+    let stream_id = stream_client.create_with_segments(
+        &yosemite,
+        &everest,
+        &10_000_000,
+        &token_address,
+        &now,
+        &segments,
+        &true,
+    );
+
+    // verify chain results
+    let stream = stream_client.get_stream(&stream_id).unwrap();
+    assert_eq!(stream.kind, StreamKind::Segments);
+    assert_eq!(stream.deposit, 10_000_000);
+    assert_eq!(stream.fee, 1_000);
+    assert_eq!(stream.start_time, now);
+    assert_eq!(stream.stop_time, now + 3_000);
+    assert_eq!(
+        token_client.balance(&yosemite),
+        100_000_000 - 10_000_000 - 1_000
+    );
+
+    // each jump advances 500 seconds
+    let expected = [
+        500_000,               // halfway through the linear segment
+        1_000_000,             // end of the linear segment
+        1_000_000 + 1_000_000, // (1/2)^2 of the convex segment
+        5_000_000,             // end of the convex segment
+        5_000_000 + 3_750_000, // 1 - (1/2)^2 of the concave segment
+        10_000_000,            // end of the stream
+    ];
+    for streamed in expected {
+        e.jump(100);
+        assert_eq!(stream_client.streamed_amount(&stream_id), streamed);
+    }
+    assert_eq!(stream_client.status(&stream_id), Some(Status::Completed));
+
+    stream_client.withdraw_max(&everest, &stream_id, &everest);
+    assert_eq!(token_client.balance(&everest), 10_000_000);
+    assert_eq!(stream_client.status(&stream_id), Some(Status::Depleted));
+}
+
+#[test]
+fn test_create_with_segments_validation() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+    let now = e.ledger().timestamp();
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &100_000_000);
+
+    let segment = |amount: i128, exponent: SegmentExponent, end_time: u64| Segment {
+        amount,
+        exponent,
+        end_time,
+    };
+    let cases: [(u64, Vec<Segment>, u32); 6] = [
+        // starts in the past
+        (
+            now - 1,
+            vec![&e, segment(10_000_000, SegmentExponent::Linear, now + 10)],
+            209,
+        ),
+        // no segments
+        (now, vec![&e], 217),
+        // exponent out of range
+        (
+            now,
+            vec![
+                &e,
+                segment(10_000_000, SegmentExponent::Convex(1), now + 10),
+            ],
+            217,
+        ),
+        // ends at the start time
+        (
+            now,
+            vec![&e, segment(10_000_000, SegmentExponent::Linear, now)],
+            218,
+        ),
+        // end times out of order
+        (
+            now,
+            vec![
+                &e,
+                segment(5_000_000, SegmentExponent::Linear, now + 20),
+                segment(5_000_000, SegmentExponent::Linear, now + 10),
+            ],
+            218,
+        ),
+        // amounts don't sum to the deposit
+        (
+            now,
+            vec![
+                &e,
+                segment(5_000_000, SegmentExponent::Linear, now + 10),
+                segment(4_000_000, SegmentExponent::Linear, now + 20),
+            ],
+            219,
+        ),
+    ];
+    for (start_time, segments, code) in cases {
+        let result = stream_client.try_create_with_segments(
+            &yosemite,
+            &everest,
+            &10_000_000,
+            &token_address,
+            &start_time,
+            &segments,
+            &true,
+        );
+        assert_eq!(result.err(), Some(Ok(Error::from_contract_error(code))));
+    }
+}
@@ -0,0 +1,166 @@
+#[cfg(test)]
+use sep_41_token::testutils::MockTokenClient;
+use soroban_sdk::{
+    testutils::{Address as _, Events},
+    vec, Address, Env, Error, IntoVal, Symbol, Vec,
+};
+use test_suites::{
+    direct_stream::{create_direct_stream, default_stream_settings},
+    env::EnvTestUtils,
+};
+use zentra_direct_stream::DirectStreamContractClient;
+
+#[test]
+fn test_sender_transfer() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+    let now = e.ledger().timestamp();
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let denali = Address::generate(&e);
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &100_000_000);
+
+    let stream_id = stream_client.create_range(
+        &yosemite,
+        &everest,
+        &10_000_000,
+        &token_address,
+        &now,
+        &(now + 10_000),
+        &true,
+        &now,
+        &0,
+        &false,
+    );
+
+    stream_client.propose_sender_transfer(&stream_id, &denali);
+    let events = e.events().all();
+    assert_eq!(
+        events.slice(events.len() - 1..),
+        vec![
+            &e,
+            (
+                stream_address.clone(),
+                (
+                    Symbol::new(&e, "sender_transfer_proposed"),
+                    stream_id,
+                    yosemite.clone()
+                )
+                    .into_val(&e),
+                denali.into_val(&e)
+            )
+        ]
+    );
+
+    // the sender is unchanged until the proposed sender accepts
+    assert_eq!(
+        stream_client.pending_sender(&stream_id),
+        Some(denali.clone())
+    );
+    assert_eq!(
+        stream_client.get_stream(&stream_id).unwrap().sender,
+        yosemite
+    );
+    let result = stream_client.try_accept_sender_transfer(&everest, &stream_id);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(231))));
+
+    stream_client.accept_sender_transfer(&denali, &stream_id);
+    let events = e.events().all();
+    assert_eq!(
+        events.slice(events.len() - 1..),
+        vec![
+            &e,
+            (
+                stream_address.clone(),
+                (
+                    Symbol::new(&e, "sender_transferred"),
+                    stream_id,
+                    yosemite.clone()
+                )
+                    .into_val(&e),
+                denali.into_val(&e)
+            )
+        ]
+    );
+    assert_eq!(stream_client.get_stream(&stream_id).unwrap().sender, denali);
+    assert_eq!(stream_client.pending_sender(&stream_id), None);
+
+    // cancel rights and refunds move to the new sender
+    e.jump(200);
+    let result = stream_client.try_cancel(&yosemite, &stream_id);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(213))));
+    let balance = token_client.balance(&yosemite);
+    stream_client.cancel(&denali, &stream_id);
+    assert_eq!(token_client.balance(&denali), 9_000_000);
+    assert_eq!(token_client.balance(&yosemite), balance);
+}
+
+#[test]
+fn test_sender_transfer_multiple() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+    let now = e.ledger().timestamp();
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let denali = Address::generate(&e);
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &100_000_000);
+    token_client.mint(&everest, &100_000_000);
+
+    let mut stream_ids = Vec::new(&e);
+    for sender in [&yosemite, &yosemite, &everest] {
+        stream_ids.push_back(stream_client.create_range(
+            sender,
+            &denali,
+            &10_000_000,
+            &token_address,
+            &now,
+            &(now + 10_000),
+            &true,
+            &now,
+            &0,
+            &false,
+        ));
+    }
+
+    // every stream must be sent by the proposing sender
+    let result =
+        stream_client.try_propose_sender_transfer_multiple(&yosemite, &stream_ids, &denali);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(230))));
+    let result =
+        stream_client.try_propose_sender_transfer_multiple(&yosemite, &Vec::new(&e), &denali);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(227))));
+
+    let yosemite_stream_ids = stream_ids.slice(0..2);
+    stream_client.propose_sender_transfer_multiple(&yosemite, &yosemite_stream_ids, &denali);
+
+    // every stream must have been proposed to the accepting sender
+    let result = stream_client.try_accept_sender_transfer_multiple(&denali, &stream_ids);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(231))));
+
+    stream_client.accept_sender_transfer_multiple(&denali, &yosemite_stream_ids);
+    for stream_id in yosemite_stream_ids.iter() {
+        assert_eq!(stream_client.get_stream(&stream_id).unwrap().sender, denali);
+        assert_eq!(stream_client.pending_sender(&stream_id), None);
+    }
+    assert_eq!(
+        stream_client
+            .get_stream(&stream_ids.get_unchecked(2))
+            .unwrap()
+            .sender,
+        everest
+    );
+}
@@ -0,0 +1,199 @@
+#[cfg(test)]
+use sep_41_token::testutils::MockTokenClient;
+use soroban_sdk::{
+    testutils::{Address as _, Events},
+    vec, Address, Env, Error, IntoVal, Symbol,
+};
+use test_suites::{
+    direct_stream::{create_direct_stream, default_stream_settings},
+    env::EnvTestUtils,
+};
+use zentra_direct_stream::{types::Role, DirectStreamContractClient};
+
+#[test]
+fn test_set_paused() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+    let now = e.ledger().timestamp();
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let denali = Address::generate(&e);
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &100_000_000);
+
+    let stream_id = stream_client.create_range(
+        &yosemite,
+        &everest,
+        &10_000_000,
+        &token_address,
+        &now,
+        &(now + 10_000),
+        &true,
+        &now,
+        &0,
+        &false,
+    );
+
+    stream_client.grant_role(&Role::Pauser, &denali);
+    stream_client.set_paused(&denali, &true, &true);
+    let events = e.events().all();
+    assert_eq!(
+        events.slice(events.len() - 1..),
+        vec![
+            &e,
+            (
+                stream_address.clone(),
+                (Symbol::new(&e, "contract_paused"), denali.clone()).into_val(&e),
+                true.into_val(&e)
+            )
+        ]
+    );
+    assert!(stream_client.paused());
+    assert!(stream_client.withdrawals_paused());
+
+    // streams can't be created or topped up
+    let result = stream_client.try_create_range(
+        &yosemite,
+        &everest,
+        &10_000_000,
+        &token_address,
+        &now,
+        &(now + 10_000),
+        &true,
+        &now,
+        &0,
+        &false,
+    );
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(234))));
+    let result = stream_client.try_top_up(&stream_id, &1_000_000, &(now + 10_000));
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(234))));
+
+    // only the recipient can withdraw
+    e.jump(200);
+    let result = stream_client.try_withdraw_max(&yosemite, &stream_id, &everest);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(234))));
+    stream_client.withdraw_max(&everest, &stream_id, &everest);
+    assert_eq!(token_client.balance(&everest), 1_000_000);
+
+    // the sender can still cancel
+    let balance = token_client.balance(&yosemite);
+    e.jump(200);
+    stream_client.cancel(&yosemite, &stream_id);
+    assert_eq!(token_client.balance(&yosemite), balance + 8_000_000);
+    stream_client.withdraw_max(&everest, &stream_id, &everest);
+    assert_eq!(token_client.balance(&everest), 2_000_000);
+
+    stream_client.set_paused(&denali, &false, &true);
+    let events = e.events().all();
+    assert_eq!(
+        events.slice(events.len() - 1..),
+        vec![
+            &e,
+            (
+                stream_address.clone(),
+                (Symbol::new(&e, "contract_unpaused"), denali.clone()).into_val(&e),
+                ().into_val(&e)
+            )
+        ]
+    );
+    assert!(!stream_client.paused());
+    assert!(!stream_client.withdrawals_paused());
+    let now = e.ledger().timestamp();
+    stream_client.create_range(
+        &yosemite,
+        &everest,
+        &10_000_000,
+        &token_address,
+        &now,
+        &(now + 10_000),
+        &true,
+        &now,
+        &0,
+        &false,
+    );
+}
+
+#[test]
+fn test_set_paused_withdrawals_open() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+    let now = e.ledger().timestamp();
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let denali = Address::generate(&e);
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &100_000_000);
+
+    let stream_id = stream_client.create_range(
+        &yosemite,
+        &everest,
+        &10_000_000,
+        &token_address,
+        &now,
+        &(now + 10_000),
+        &true,
+        &now,
+        &0,
+        &false,
+    );
+    stream_client.approve_operator(&everest, &denali, &(now + 10_000));
+
+    stream_client.set_paused(&settings.admin, &true, &false);
+    let events = e.events().all();
+    assert_eq!(
+        events.slice(events.len() - 1..),
+        vec![
+            &e,
+            (
+                stream_address.clone(),
+                (Symbol::new(&e, "contract_paused"), settings.admin.clone()).into_val(&e),
+                false.into_val(&e)
+            )
+        ]
+    );
+    assert!(stream_client.paused());
+    assert!(!stream_client.withdrawals_paused());
+
+    // streams can't be topped up
+    let result = stream_client.try_top_up(&stream_id, &1_000_000, &(now + 10_000));
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(234))));
+
+    // but the sender and operators can still withdraw to the recipient
+    e.jump(200);
+    stream_client.withdraw_max(&yosemite, &stream_id, &everest);
+    assert_eq!(token_client.balance(&everest), 1_000_000);
+    e.jump(200);
+    stream_client.withdraw_max(&denali, &stream_id, &everest);
+    assert_eq!(token_client.balance(&everest), 2_000_000);
+}
+
+#[test]
+fn test_set_paused_not_pauser() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let denali = Address::generate(&e);
+    let settings = default_stream_settings(&e);
+    let (stream_address, _) = create_direct_stream(&e, &settings.admin, &settings);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    let result = stream_client.try_set_paused(&denali, &true, &true);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(4))));
+
+    // the admin holds the pauser role implicitly
+    stream_client.set_paused(&settings.admin, &true, &true);
+    assert!(stream_client.paused());
+}
@@ -0,0 +1,77 @@
+#[cfg(test)]
+use sep_41_token::testutils::MockTokenClient;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, Env,
+};
+use test_suites::{
+    direct_stream::{create_direct_stream, default_stream_settings},
+    env::EnvTestUtils,
+};
+use zentra_direct_stream::DirectStreamContractClient;
+
+/// Set the ledger timestamp without advancing the sequence, keeping every entry live
+fn set_timestamp(e: &Env, timestamp: u64) {
+    e.ledger().with_mut(|li| li.timestamp = timestamp);
+}
+
+/// floor(deposit * elapsed / duration), computed without a wide intermediate product
+fn expected_streamed(deposit: i128, elapsed: i128, duration: i128) -> i128 {
+    (deposit / duration) * elapsed + (deposit % duration) * elapsed / duration
+}
+
+#[test]
+fn test_streamed_amount_exact() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+    let now = e.ledger().timestamp();
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &(i128::MAX / 2));
+
+    // 7 over 3 seconds streams 2.33.. per second, always rounded down
+    let stream_id = stream_client.create_range(
+        &yosemite,
+        &everest,
+        &7,
+        &token_address,
+        &now,
+        &(now + 3),
+        &true,
+        &now,
+        &0,
+        &false,
+    );
+    for (elapsed, streamed) in [(0, 0), (1, 2), (2, 4), (3, 7)] {
+        set_timestamp(&e, now + elapsed);
+        assert_eq!(stream_client.streamed_amount(&stream_id), streamed);
+    }
+
+    // a deposit large enough that deposit * elapsed overflows an i128
+    set_timestamp(&e, now);
+    let deposit = i128::MAX / 4;
+    let stream_id = stream_client.create_range(
+        &yosemite,
+        &everest,
+        &deposit,
+        &token_address,
+        &now,
+        &(now + 1_000),
+        &true,
+        &now,
+        &0,
+        &false,
+    );
+    set_timestamp(&e, now + 999);
+    assert_eq!(
+        stream_client.streamed_amount(&stream_id),
+        expected_streamed(deposit, 999, 1_000)
+    );
+}
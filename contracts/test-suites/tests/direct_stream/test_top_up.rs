@@ -0,0 +1,192 @@
+#[cfg(test)]
+use sep_41_token::testutils::MockTokenClient;
+use soroban_sdk::{
+    testutils::{Address as _, Events},
+    vec, Address, Env, Error, IntoVal, Symbol,
+};
+use test_suites::{
+    direct_stream::{create_direct_stream, default_stream_settings},
+    env::EnvTestUtils,
+};
+use zentra_direct_stream::{
+    types::{Segment, SegmentExponent},
+    DirectStreamContractClient,
+};
+
+#[test]
+fn test_top_up() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+    let now = e.ledger().timestamp();
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &100_000_000);
+
+    // 1_000 per second for 10_000 seconds
+    let stream_id = stream_client.create_range(
+        &yosemite,
+        &everest,
+        &10_000_000,
+        &token_address,
+        &now,
+        &(now + 10_000),
+        &true,
+        &now,
+        &0,
+        &false,
+    );
+
+    // each jump of 200 ledgers advances 1_000 seconds
+    e.jump(200);
+    stream_client.top_up(&stream_id, &10_000_000, &(now + 19_000));
+    let events = e.events().all();
+    assert_eq!(
+        events.slice(events.len() - 1..),
+        vec![
+            &e,
+            (
+                stream_address.clone(),
+                (Symbol::new(&e, "stream_topped_up"), stream_id).into_val(&e),
+                (10_000_000_i128, 1_000_i128, now + 19_000).into_val(&e)
+            )
+        ]
+    );
+
+    // verify chain results
+    let stream = stream_client.get_stream(&stream_id).unwrap();
+    assert_eq!(stream.deposit, 20_000_000);
+    assert_eq!(stream.fee, 2_000);
+    assert_eq!(stream.cliff_amount, 1_000_000);
+    assert_eq!(stream.start_time, now + 1_000);
+    assert_eq!(stream.stop_time, now + 19_000);
+    assert_eq!(stream_client.accrued_fees(&token_address), 2_000);
+    assert_eq!(
+        token_client.balance(&yosemite),
+        100_000_000 - 20_000_000 - 2_000
+    );
+
+    // the accrued amount is kept, and the remaining 19_000_000 streams over 18_000 seconds
+    assert_eq!(stream_client.streamed_amount(&stream_id), 1_000_000);
+    e.jump(360);
+    assert_eq!(
+        stream_client.streamed_amount(&stream_id),
+        1_000_000 + 1_900_000
+    );
+    e.jump(3_240);
+    assert_eq!(stream_client.streamed_amount(&stream_id), 20_000_000);
+}
+
+#[test]
+fn test_top_up_before_cliff() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+    let now = e.ledger().timestamp();
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &100_000_000);
+
+    let stream_id = stream_client.create_range(
+        &yosemite,
+        &everest,
+        &10_000_000,
+        &token_address,
+        &now,
+        &(now + 10_000),
+        &true,
+        &(now + 5_000),
+        &0,
+        &false,
+    );
+
+    // nothing has accrued yet, so the rate is recalculated over the original schedule
+    e.jump(200);
+    stream_client.top_up(&stream_id, &10_000_000, &(now + 10_000));
+    let stream = stream_client.get_stream(&stream_id).unwrap();
+    assert_eq!(stream.start_time, now);
+    assert_eq!(stream.cliff_time, now + 5_000);
+
+    e.jump(800);
+    assert_eq!(stream_client.streamed_amount(&stream_id), 10_000_000);
+}
+
+#[test]
+fn test_top_up_validation() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+    let now = e.ledger().timestamp();
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &100_000_000);
+
+    let stream_id = stream_client.create_range(
+        &yosemite,
+        &everest,
+        &10_000_000,
+        &token_address,
+        &now,
+        &(now + 10_000),
+        &true,
+        &now,
+        &0,
+        &false,
+    );
+    let segments_id = stream_client.create_with_segments(
+        &yosemite,
+        &everest,
+        &10_000_000,
+        &token_address,
+        &now,
+        &vec![
+            &e,
+            Segment {
+                amount: 10_000_000,
+                exponent: SegmentExponent::Linear,
+                end_time: now + 10_000,
+            },
+        ],
+        &true,
+    );
+
+    let cases = [
+        // (stream_id, amount, new_stop_time, error code)
+        (stream_id, 0, now + 10_000, 208),
+        (stream_id, 1_000, now + 9_999, 226),
+        // a dust top up can't stretch the schedule of the remaining deposit
+        (stream_id, 1, now + 1_000_000, 226),
+        (segments_id, 1_000, now + 10_000, 225),
+    ];
+    for (stream_id, amount, new_stop_time, code) in cases {
+        let result = stream_client.try_top_up(&stream_id, &amount, &new_stop_time);
+        assert_eq!(result.err(), Some(Ok(Error::from_contract_error(code))));
+    }
+
+    stream_client.pause(&stream_id);
+    let result = stream_client.try_top_up(&stream_id, &1_000, &(now + 10_000));
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(223))));
+    stream_client.resume(&stream_id);
+
+    e.jump(2_000);
+    let result = stream_client.try_top_up(&stream_id, &1_000, &(now + 20_000));
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(207))));
+}
@@ -0,0 +1,154 @@
+#[cfg(test)]
+use sep_41_token::testutils::MockTokenClient;
+use soroban_sdk::{testutils::Address as _, vec, Address, Env, Error, Vec};
+use test_suites::{
+    direct_stream::{create_direct_stream, default_stream_settings},
+    env::EnvTestUtils,
+};
+use zentra_direct_stream::{
+    types::{Status, StreamKind, Tranche},
+    DirectStreamContractClient,
+};
+
+#[test]
+fn test_create_with_tranches() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+    let start_time = e.ledger().timestamp() + 500;
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &100_000_000);
+
+    // unlock 2_500_000 every 1_000 seconds
+    let mut tranches = Vec::new(&e);
+    for i in 1..=4 {
+        tranches.push_back(Tranche {
+            amount: 2_500_000,
+            unlock_time: start_time + i * 1_000,
+        });
+    }
+    let stream_id = stream_client.create_with_tranches(
+        &yosemite,
+        &everest,
+        &10_000_000,
+        &token_address,
+        &start_time,
+        &tranches,
+        &true,
+    );
+
+    // verify chain results
+    let stream = stream_client.get_stream(&stream_id).unwrap();
+    assert_eq!(stream.kind, StreamKind::Tranches);
+    assert_eq!(stream.stop_time, start_time + 4_000);
+    assert_eq!(stream_client.status(&stream_id), Some(Status::Pending));
+    assert_eq!(stream_client.unlocked_tranches(&stream_id), 0);
+
+    // nothing is streamed between unlocks
+    e.jump(200);
+    assert_eq!(stream_client.status(&stream_id), Some(Status::Active));
+    assert_eq!(stream_client.streamed_amount(&stream_id), 0);
+    assert_eq!(stream_client.unlocked_tranches(&stream_id), 0);
+
+    e.jump(100);
+    assert_eq!(stream_client.streamed_amount(&stream_id), 2_500_000);
+    assert_eq!(stream_client.unlocked_tranches(&stream_id), 1);
+
+    e.jump(199);
+    assert_eq!(stream_client.streamed_amount(&stream_id), 2_500_000);
+
+    e.jump(1);
+    assert_eq!(stream_client.streamed_amount(&stream_id), 5_000_000);
+    assert_eq!(stream_client.unlocked_tranches(&stream_id), 2);
+
+    stream_client.withdraw_max(&everest, &stream_id, &everest);
+    assert_eq!(token_client.balance(&everest), 5_000_000);
+
+    // the sender is refunded the tranches that have not unlocked yet
+    e.jump(200);
+    assert_eq!(stream_client.unlocked_tranches(&stream_id), 3);
+    stream_client.cancel(&yosemite, &stream_id);
+    assert_eq!(
+        token_client.balance(&yosemite),
+        100_000_000 - 10_000_000 - 1_000 + 2_500_000
+    );
+    assert_eq!(stream_client.withdrawable_amount(&stream_id), 2_500_000);
+
+    stream_client.withdraw_max(&everest, &stream_id, &everest);
+    assert_eq!(token_client.balance(&everest), 7_500_000);
+    assert_eq!(stream_client.status(&stream_id), Some(Status::Depleted));
+
+    // the tranches refunded on cancellation never unlock
+    e.jump(2_000);
+    assert_eq!(stream_client.unlocked_tranches(&stream_id), 3);
+}
+
+#[test]
+fn test_create_with_tranches_validation() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+    let now = e.ledger().timestamp();
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &100_000_000);
+
+    let tranche = |amount: i128, unlock_time: u64| Tranche {
+        amount,
+        unlock_time,
+    };
+    let cases: [(Vec<Tranche>, u32); 5] = [
+        // no tranches
+        (vec![&e], 220),
+        // negative amount
+        (
+            vec![&e, tranche(-1, now + 10), tranche(10_000_001, now + 20)],
+            220,
+        ),
+        // unlocks at the start time
+        (vec![&e, tranche(10_000_000, now)], 221),
+        // unlock times out of order
+        (
+            vec![
+                &e,
+                tranche(5_000_000, now + 20),
+                tranche(5_000_000, now + 10),
+            ],
+            221,
+        ),
+        // amounts don't sum to the deposit
+        (
+            vec![
+                &e,
+                tranche(5_000_000, now + 10),
+                tranche(5_000_001, now + 20),
+            ],
+            222,
+        ),
+    ];
+    for (tranches, code) in cases {
+        let result = stream_client.try_create_with_tranches(
+            &yosemite,
+            &everest,
+            &10_000_000,
+            &token_address,
+            &now,
+            &tranches,
+            &true,
+        );
+        assert_eq!(result.err(), Some(Ok(Error::from_contract_error(code))));
+    }
+}
@@ -0,0 +1,222 @@
+#[cfg(test)]
+use sep_41_token::testutils::MockTokenClient;
+use soroban_sdk::{
+    testutils::{Address as _, Events},
+    vec, Address, Env, Error, IntoVal, Symbol,
+};
+use test_suites::{
+    direct_stream::{create_direct_stream, default_stream_settings},
+    env::EnvTestUtils,
+};
+use zentra_direct_stream::DirectStreamContractClient;
+
+#[test]
+fn test_transfer() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+    let now = e.ledger().timestamp();
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let denali = Address::generate(&e);
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &100_000_000);
+
+    let stream_id = stream_client.create_range(
+        &yosemite,
+        &everest,
+        &10_000_000,
+        &token_address,
+        &now,
+        &(now + 10_000),
+        &true,
+        &now,
+        &0,
+        &true,
+    );
+    assert_eq!(stream_client.owner_of(&stream_id), everest);
+
+    e.jump(200);
+    stream_client.transfer(&everest, &denali, &stream_id);
+    let events = e.events().all();
+    assert_eq!(
+        events.slice(events.len() - 1..),
+        vec![
+            &e,
+            (
+                stream_address.clone(),
+                (Symbol::new(&e, "transfer"), everest.clone(), denali.clone()).into_val(&e),
+                stream_id.into_val(&e)
+            )
+        ]
+    );
+
+    // withdraw rights follow the new owner, including what accrued before the transfer
+    assert_eq!(stream_client.owner_of(&stream_id), denali);
+    let result = stream_client.try_withdraw_max(&everest, &stream_id, &everest);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(213))));
+    stream_client.withdraw_max(&denali, &stream_id, &denali);
+    assert_eq!(token_client.balance(&denali), 1_000_000);
+
+    // the sender can only withdraw to the current owner
+    e.jump(200);
+    let result = stream_client.try_withdraw_max(&yosemite, &stream_id, &everest);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(214))));
+    stream_client.withdraw_max(&yosemite, &stream_id, &denali);
+    assert_eq!(token_client.balance(&denali), 2_000_000);
+}
+
+#[test]
+fn test_transfer_from() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+    let now = e.ledger().timestamp();
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let denali = Address::generate(&e);
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &100_000_000);
+
+    let stream_id = stream_client.create_range(
+        &yosemite,
+        &everest,
+        &10_000_000,
+        &token_address,
+        &now,
+        &(now + 10_000),
+        &true,
+        &now,
+        &0,
+        &true,
+    );
+
+    // an approved address can transfer the stream once
+    stream_client.approve(&everest, &Some(denali.clone()), &stream_id);
+    let events = e.events().all();
+    assert_eq!(
+        events.slice(events.len() - 1..),
+        vec![
+            &e,
+            (
+                stream_address.clone(),
+                (Symbol::new(&e, "approve"), everest.clone(), stream_id).into_val(&e),
+                Some(denali.clone()).into_val(&e)
+            )
+        ]
+    );
+    assert_eq!(stream_client.get_approved(&stream_id), Some(denali.clone()));
+
+    stream_client.transfer_from(&denali, &everest, &denali, &stream_id);
+    assert_eq!(stream_client.owner_of(&stream_id), denali);
+    assert_eq!(stream_client.get_approved(&stream_id), None);
+
+    // an operator can transfer any of the owner's streams until unapproved
+    stream_client.approve_for_all(&denali, &yosemite, &true);
+    let events = e.events().all();
+    assert_eq!(
+        events.slice(events.len() - 1..),
+        vec![
+            &e,
+            (
+                stream_address.clone(),
+                (
+                    Symbol::new(&e, "approve_for_all"),
+                    denali.clone(),
+                    yosemite.clone()
+                )
+                    .into_val(&e),
+                true.into_val(&e)
+            )
+        ]
+    );
+    assert!(stream_client.is_approved_for_all(&denali, &yosemite));
+
+    stream_client.transfer_from(&yosemite, &denali, &everest, &stream_id);
+    assert_eq!(stream_client.owner_of(&stream_id), everest);
+
+    stream_client.approve_for_all(&denali, &yosemite, &false);
+    assert!(!stream_client.is_approved_for_all(&denali, &yosemite));
+}
+
+#[test]
+fn test_transfer_validation() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+    let now = e.ledger().timestamp();
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let denali = Address::generate(&e);
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &100_000_000);
+
+    let stream_id = stream_client.create_range(
+        &yosemite,
+        &everest,
+        &10_000_000,
+        &token_address,
+        &now,
+        &(now + 10_000),
+        &true,
+        &now,
+        &0,
+        &false,
+    );
+
+    // non-transferable streams can't be transferred or approved
+    let result = stream_client.try_transfer(&everest, &denali, &stream_id);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(228))));
+    let result = stream_client.try_approve(&everest, &Some(denali.clone()), &stream_id);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(228))));
+
+    let stream_id = stream_client.create_range(
+        &yosemite,
+        &everest,
+        &10_000_000,
+        &token_address,
+        &now,
+        &(now + 10_000),
+        &true,
+        &now,
+        &0,
+        &true,
+    );
+
+    // only the owner can transfer the stream
+    let result = stream_client.try_transfer(&yosemite, &denali, &stream_id);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(214))));
+    let result = stream_client.try_approve(&yosemite, &Some(denali.clone()), &stream_id);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(214))));
+
+    // or an address it approved
+    let result = stream_client.try_transfer_from(&denali, &everest, &denali, &stream_id);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(229))));
+
+    // an operator of someone else can't transfer the stream
+    stream_client.approve_for_all(&yosemite, &denali, &true);
+    let result = stream_client.try_transfer_from(&denali, &yosemite, &denali, &stream_id);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(214))));
+
+    // the stream can't be transferred to the contract itself
+    let result = stream_client.try_transfer(&everest, &stream_address, &stream_id);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(215))));
+    stream_client.approve(&everest, &Some(denali.clone()), &stream_id);
+    let result = stream_client.try_transfer_from(&denali, &everest, &stream_address, &stream_id);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(215))));
+}
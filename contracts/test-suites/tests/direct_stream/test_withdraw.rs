@@ -2,13 +2,13 @@
 use sep_41_token::testutils::MockTokenClient;
 use soroban_sdk::{
     testutils::{Address as _, AuthorizedFunction, AuthorizedInvocation},
-    vec, Address, Env, Symbol, IntoVal,
+    vec, Address, Env, Error, IntoVal, Symbol,
 };
 use test_suites::{
     direct_stream::{create_direct_stream, default_stream_settings},
     env::EnvTestUtils, ONE_DAY_LEDGERS,
 };
-use zentra_direct_stream::DirectStreamContractClient;
+use zentra_direct_stream::{types::Status, DirectStreamContractClient};
 
 #[test]
 fn test_withdraw() {
@@ -41,6 +41,8 @@ fn test_withdraw() {
         &deadline,
         &true,
         &e.ledger().timestamp(),
+        &0,
+        &false,
     );
 
     // advance ledger
@@ -79,4 +81,268 @@ fn test_withdraw() {
     let stream = stream_client.get_stream(&stream_id).unwrap();
 
     assert_eq!(stream.withdrawn, withdraw_amount);
+    assert_eq!(
+        stream_client.withdrawable_amount(&stream_id),
+        amount_streamed - withdraw_amount
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #201)")]
+fn test_withdraw_exceeds_withdrawable_amount() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+    let deadline = e.ledger().timestamp() + (365 * ONE_DAY_LEDGERS) as u64;
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &100_000_000);
+
+    // create two streams so the contract holds more than a single stream's funds
+    for _ in 0..2 {
+        stream_client.create_range(
+            &yosemite,
+            &everest,
+            &10_000_000,
+            &token_address,
+            &e.ledger().timestamp(),
+            &deadline,
+            &true,
+            &e.ledger().timestamp(),
+            &0,
+            &false,
+        );
+    }
+    e.jump(5 * ONE_DAY_LEDGERS);
+
+    // withdrawing the full streamed amount twice must not pay out twice
+    let amount_streamed = stream_client.streamed_amount(&1);
+    stream_client.withdraw(&everest, &everest, &1, &amount_streamed);
+    assert_eq!(stream_client.withdrawable_amount(&1), 0);
+
+    stream_client.withdraw(&everest, &everest, &1, &amount_streamed);
+}
+
+#[test]
+fn test_withdraw_depletes_stream() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+    let deadline = e.ledger().timestamp() + (365 * ONE_DAY_LEDGERS) as u64;
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &100_000_000);
+
+    let stream_amount = 10_000_000_i128;
+    let stream_id = stream_client.create_range(
+        &yosemite,
+        &everest,
+        &stream_amount,
+        &token_address,
+        &e.ledger().timestamp(),
+        &deadline,
+        &true,
+        &e.ledger().timestamp(),
+        &0,
+        &false,
+    );
+    e.jump(30 * ONE_DAY_LEDGERS);
+
+    let amount_streamed = stream_client.streamed_amount(&stream_id);
+    stream_client.withdraw(&everest, &everest, &stream_id, &amount_streamed);
+    assert!(!stream_client.get_stream(&stream_id).unwrap().is_depleted);
+    assert_eq!(stream_client.status(&stream_id), Some(Status::Active));
+
+    // withdraw part of the newly streamed amount
+    e.jump(30 * ONE_DAY_LEDGERS);
+    stream_client.withdraw(&everest, &everest, &stream_id, &1);
+
+    e.jump(30 * ONE_DAY_LEDGERS);
+    assert_eq!(stream_client.status(&stream_id), Some(Status::Completed));
+    assert_eq!(
+        stream_client.withdrawable_amount(&stream_id),
+        stream_amount - amount_streamed - 1
+    );
+
+    stream_client.withdraw(
+        &everest,
+        &everest,
+        &stream_id,
+        &(stream_amount - amount_streamed - 1),
+    );
+
+    let stream = stream_client.get_stream(&stream_id).unwrap();
+    assert_eq!(stream.withdrawn, stream_amount);
+    assert!(stream.is_depleted);
+    assert_eq!(stream_client.status(&stream_id), Some(Status::Depleted));
+    assert_eq!(token_client.balance(&everest), stream_amount);
+}
+
+#[test]
+fn test_withdraw_max() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+    let deadline = e.ledger().timestamp() + (365 * ONE_DAY_LEDGERS) as u64;
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let denali = Address::generate(&e);
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &100_000_000);
+
+    let stream_id = stream_client.create_range(
+        &yosemite,
+        &everest,
+        &10_000_000,
+        &token_address,
+        &e.ledger().timestamp(),
+        &deadline,
+        &true,
+        &e.ledger().timestamp(),
+        &0,
+        &false,
+    );
+    e.jump(5 * ONE_DAY_LEDGERS);
+
+    // the recipient can direct the withdrawal to any address
+    let amount_streamed = stream_client.streamed_amount(&stream_id);
+    let withdrawn = stream_client.withdraw_max(&everest, &stream_id, &denali);
+
+    // verify auths
+    assert_eq!(
+        e.auths()[0],
+        (
+            everest.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    stream_address.clone(),
+                    Symbol::new(&e, "withdraw_max"),
+                    vec![
+                        &e,
+                        everest.to_val(),
+                        stream_id.into_val(&e),
+                        denali.to_val()
+                    ]
+                )),
+                sub_invocations: std::vec![]
+            }
+        )
+    );
+
+    // verify chain results
+    assert_eq!(withdrawn, amount_streamed);
+    assert_eq!(token_client.balance(&denali), amount_streamed);
+    assert_eq!(token_client.balance(&everest), 0);
+    assert_eq!(stream_client.withdrawable_amount(&stream_id), 0);
+    assert_eq!(stream_client.withdraw_max(&everest, &stream_id, &denali), 0);
+
+    // the sender can withdraw on behalf of the recipient
+    e.jump(ONE_DAY_LEDGERS);
+    let withdrawable = stream_client.withdrawable_amount(&stream_id);
+    assert_eq!(
+        stream_client.withdraw_max(&yosemite, &stream_id, &everest),
+        withdrawable
+    );
+    assert_eq!(token_client.balance(&everest), withdrawable);
+}
+
+#[test]
+fn test_withdraw_sender_to_other_address() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+    let deadline = e.ledger().timestamp() + (365 * ONE_DAY_LEDGERS) as u64;
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &100_000_000);
+
+    let stream_id = stream_client.create_range(
+        &yosemite,
+        &everest,
+        &10_000_000,
+        &token_address,
+        &e.ledger().timestamp(),
+        &deadline,
+        &true,
+        &e.ledger().timestamp(),
+        &0,
+        &false,
+    );
+    e.jump(5 * ONE_DAY_LEDGERS);
+
+    // the sender can only withdraw to the recipient
+    let result = stream_client.try_withdraw(&yosemite, &yosemite, &stream_id, &100_000);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(214))));
+    let result = stream_client.try_withdraw_max(&yosemite, &stream_id, &yosemite);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(214))));
+    assert_eq!(stream_client.get_stream(&stream_id).unwrap().withdrawn, 0);
+}
+
+#[test]
+fn test_withdraw_validation() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+    let deadline = e.ledger().timestamp() + (365 * ONE_DAY_LEDGERS) as u64;
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let denali = Address::generate(&e);
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &100_000_000);
+
+    let stream_id = stream_client.create_range(
+        &yosemite,
+        &everest,
+        &10_000_000,
+        &token_address,
+        &e.ledger().timestamp(),
+        &deadline,
+        &true,
+        &e.ledger().timestamp(),
+        &0,
+        &false,
+    );
+    e.jump(5 * ONE_DAY_LEDGERS);
+
+    let cases = [
+        // (caller, to, stream_id, amount, error code)
+        (&everest, &everest, 2, 100_000, 200),
+        (&everest, &everest, stream_id, 0, 208),
+        (&denali, &everest, stream_id, 100_000, 213),
+        (&yosemite, &denali, stream_id, 100_000, 214),
+        (&everest, &stream_address, stream_id, 100_000, 215),
+        (&everest, &everest, stream_id, 10_000_000, 201),
+    ];
+    for (caller, to, stream_id, amount, code) in cases {
+        let result = stream_client.try_withdraw(caller, to, &stream_id, &amount);
+        assert_eq!(result.err(), Some(Ok(Error::from_contract_error(code))));
+    }
 }
@@ -0,0 +1,230 @@
+#[cfg(test)]
+use sep_41_token::testutils::MockTokenClient;
+use soroban_sdk::{testutils::Address as _, vec, Address, Env, Error, Vec};
+use test_suites::{
+    common::{count_events, create_stellar_token},
+    direct_stream::{create_direct_stream, create_direct_stream_wasm, default_stream_settings},
+    env::EnvTestUtils,
+};
+use zentra_direct_stream::{types::RangeParams, DirectStreamContractClient};
+
+#[test]
+fn test_withdraw_multiple() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+    let now = e.ledger().timestamp();
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let denali = Address::generate(&e);
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let (other_token_address, _) = create_stellar_token(&e, &settings.admin);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let other_token_client = MockTokenClient::new(&e, &other_token_address);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &100_000_000);
+    other_token_client.mint(&yosemite, &100_000_000);
+
+    let mut stream_ids = Vec::new(&e);
+    for (token_address, amount) in [
+        (&token_address, 10_000_000),
+        (&other_token_address, 20_000_000),
+        (&token_address, 30_000_000),
+    ] {
+        stream_ids.push_back(stream_client.create_range(
+            &yosemite,
+            &everest,
+            &amount,
+            token_address,
+            &now,
+            &(now + 10_000),
+            &true,
+            &now,
+            &0,
+            &false,
+        ));
+    }
+
+    // each stream is 10% streamed
+    e.jump(200);
+    let transfers = count_events(&e, &token_address, "transfer");
+    let other_transfers = count_events(&e, &other_token_address, "transfer");
+    let amounts = stream_client.withdraw_multiple(&everest, &stream_ids, &denali);
+
+    // verify chain results
+    assert_eq!(amounts, vec![&e, 1_000_000, 2_000_000, 3_000_000]);
+    assert_eq!(token_client.balance(&denali), 4_000_000);
+    assert_eq!(other_token_client.balance(&denali), 2_000_000);
+    for stream_id in stream_ids.iter() {
+        assert_eq!(stream_client.withdrawable_amount(&stream_id), 0);
+    }
+
+    // one withdrawal event per stream, and one transfer per token
+    assert_eq!(count_events(&e, &stream_address, "stream_withdrawn"), 3);
+    assert_eq!(count_events(&e, &token_address, "transfer"), transfers + 1);
+    assert_eq!(
+        count_events(&e, &other_token_address, "transfer"),
+        other_transfers + 1
+    );
+
+    // nothing is left to withdraw right away
+    let amounts = stream_client.withdraw_multiple(&everest, &stream_ids, &denali);
+    assert_eq!(amounts, vec![&e, 0, 0, 0]);
+}
+
+#[test]
+fn test_withdraw_multiple_is_atomic() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+    let now = e.ledger().timestamp();
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let denali = Address::generate(&e);
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream(&e, &settings.admin, &settings);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &100_000_000);
+
+    let mut stream_ids = Vec::new(&e);
+    for recipient in [&everest, &denali] {
+        stream_ids.push_back(stream_client.create_range(
+            &yosemite,
+            recipient,
+            &10_000_000,
+            &token_address,
+            &now,
+            &(now + 10_000),
+            &true,
+            &now,
+            &0,
+            &false,
+        ));
+    }
+
+    e.jump(200);
+    let result = stream_client.try_withdraw_multiple(&everest, &stream_ids, &everest);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(213))));
+    assert_eq!(token_client.balance(&everest), 0);
+    assert_eq!(
+        stream_client.withdrawable_amount(&stream_ids.get_unchecked(0)),
+        1_000_000
+    );
+
+    let result = stream_client.try_withdraw_multiple(&everest, &vec![&e, 3], &everest);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(200))));
+}
+
+/// Each stream is its own ledger entry, so the number of streams that fit in one
+/// transaction is bound by the network's ledger entry limits before the cpu and memory
+/// limits. As of protocol 20 a transaction can write 25 entries, and with the token
+/// balances of the contract and `to` that leaves room for about 20 streams of a single
+/// token, which use roughly a fifth of the cpu limit.
+#[test]
+fn test_withdraw_multiple_budget() {
+    // Network limits per transaction, as of protocol 20
+    const MAX_CPU_INSTRUCTIONS: u64 = 100_000_000;
+    const MAX_MEMORY_BYTES: u64 = 40 * 1024 * 1024;
+    // The largest batch, which keeps within the 25 ledger entries a transaction can write
+    const STREAM_COUNT: u32 = 20;
+
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+    let now = e.ledger().timestamp();
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let settings = default_stream_settings(&e);
+    let (stream_address, token_address) = create_direct_stream_wasm(&e, &settings.admin, &settings);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let stream_client = DirectStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &1_000_000_000);
+
+    let mut params = Vec::new(&e);
+    for _ in 0..STREAM_COUNT {
+        params.push_back(RangeParams {
+            recipient: everest.clone(),
+            amount: 10_000_000,
+            start_time: now,
+            stop_time: now + 10_000,
+            cliff_time: now,
+            cliff_amount: 0,
+            cancellable: true,
+            transferable: false,
+        });
+    }
+    e.budget().reset_unlimited();
+    let stream_ids = stream_client.create_range_batch(&yosemite, &token_address, &params);
+    let extra_stream_ids =
+        stream_client.create_range_batch(&yosemite, &token_address, &params.slice(0..1));
+    e.jump(200);
+
+    // one stream more than the largest batch is rejected
+    let mut oversized_params = params.clone();
+    oversized_params.append(&params.slice(0..1));
+    let result = stream_client.try_create_range_batch(&yosemite, &token_address, &oversized_params);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(227))));
+    let mut oversized_stream_ids = stream_ids.clone();
+    oversized_stream_ids.append(&extra_stream_ids);
+    let result = stream_client.try_withdraw_multiple(&everest, &oversized_stream_ids, &everest);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(227))));
+
+    e.budget().reset_default();
+    let amounts = stream_client.withdraw_multiple(&everest, &stream_ids, &everest);
+    assert_eq!(amounts.len(), STREAM_COUNT);
+    assert_eq!(token_client.balance(&everest), 20_000_000);
+    assert!(e.budget().cpu_instruction_cost() < MAX_CPU_INSTRUCTIONS);
+    assert!(e.budget().memory_bytes_cost() < MAX_MEMORY_BYTES);
+
+    // each token also writes two balances, so 3 streams for each of 5 tokens fill the
+    // 25 writes while 2 streams for each of 9 tokens exceed them
+    e.budget().reset_unlimited();
+    let now = e.ledger().timestamp();
+    let mut token_stream_ids = Vec::new(&e);
+    for _ in 0..9 {
+        let (token_address, token_client) = create_stellar_token(&e, &settings.admin);
+        token_client.mint(&yosemite, &100_000_000);
+        let mut stream_ids = Vec::new(&e);
+        for _ in 0..3 {
+            stream_ids.push_back(stream_client.create_range(
+                &yosemite,
+                &everest,
+                &10_000_000,
+                &token_address,
+                &now,
+                &(now + 10_000),
+                &true,
+                &now,
+                &0,
+                &false,
+            ));
+        }
+        token_stream_ids.push_back(stream_ids);
+    }
+    e.jump(200);
+
+    let mut oversized_stream_ids = Vec::new(&e);
+    for stream_ids in token_stream_ids.iter() {
+        oversized_stream_ids.append(&stream_ids.slice(0..2));
+    }
+    let result = stream_client.try_withdraw_multiple(&everest, &oversized_stream_ids, &everest);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(227))));
+
+    let mut stream_ids = Vec::new(&e);
+    for token_streams in token_stream_ids.slice(0..5).iter() {
+        stream_ids.append(&token_streams);
+    }
+    e.budget().reset_default();
+    let amounts = stream_client.withdraw_multiple(&everest, &stream_ids, &everest);
+    assert_eq!(amounts.len(), 15);
+    assert!(e.budget().cpu_instruction_cost() < MAX_CPU_INSTRUCTIONS);
+    assert!(e.budget().memory_bytes_cost() < MAX_MEMORY_BYTES);
+}
@@ -0,0 +1,6 @@
+mod test_adjust_rate;
+mod test_create;
+mod test_pause;
+mod test_solvency;
+mod test_withdraw;
+mod test_withdraw_balance;
@@ -0,0 +1,57 @@
+#[cfg(test)]
+use sep_41_token::testutils::MockTokenClient;
+use soroban_sdk::{testutils::Address as _, Address, Env};
+use test_suites::{env::EnvTestUtils, flow_stream::create_flow_stream};
+use zentra_flow_stream::FlowStreamContractClient;
+
+#[test]
+fn test_adjust_rate() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let admin = Address::generate(&e);
+    let (stream_address, token_address) = create_flow_stream(&e, &admin);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let stream_client = FlowStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &100_000_000);
+
+    let stream_id = stream_client.create(&yosemite, &everest, &token_address, &10);
+    stream_client.deposit(&yosemite, &token_address, &1_000_000);
+
+    // debt accrued at the old rate is kept
+    e.jump(1_000);
+    stream_client.adjust_rate(&stream_id, &25);
+    assert_eq!(stream_client.total_debt(&stream_id), 50_000);
+
+    e.jump(1_000);
+    assert_eq!(stream_client.total_debt(&stream_id), 50_000 + 125_000);
+    assert_eq!(
+        stream_client
+            .get_stream(&stream_id)
+            .unwrap()
+            .rate_per_second,
+        25
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #205)")]
+fn test_adjust_rate_paused() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let admin = Address::generate(&e);
+    let (stream_address, token_address) = create_flow_stream(&e, &admin);
+    let stream_client = FlowStreamContractClient::new(&e, &stream_address);
+
+    let stream_id = stream_client.create(&yosemite, &everest, &token_address, &10);
+    stream_client.pause(&stream_id);
+    stream_client.adjust_rate(&stream_id, &25);
+}
@@ -0,0 +1,80 @@
+#[cfg(test)]
+use sep_41_token::testutils::MockTokenClient;
+use soroban_sdk::{
+    testutils::{Address as _, AuthorizedFunction, AuthorizedInvocation},
+    vec, Address, Env, IntoVal, Symbol,
+};
+use test_suites::{env::EnvTestUtils, flow_stream::create_flow_stream};
+use zentra_flow_stream::FlowStreamContractClient;
+
+#[test]
+fn test_create_and_deposit() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let admin = Address::generate(&e);
+    let (stream_address, token_address) = create_flow_stream(&e, &admin);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let stream_client = FlowStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &100_000_000);
+
+    let stream_id = stream_client.create(&yosemite, &everest, &token_address, &10);
+
+    // verify auths
+    assert_eq!(
+        e.auths()[0],
+        (
+            yosemite.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    stream_address.clone(),
+                    Symbol::new(&e, "create"),
+                    vec![
+                        &e,
+                        yosemite.to_val(),
+                        everest.to_val(),
+                        token_address.to_val(),
+                        10_i128.into_val(&e)
+                    ]
+                )),
+                sub_invocations: std::vec![]
+            }
+        )
+    );
+
+    // verify chain results
+    let stream = stream_client.get_stream(&stream_id).unwrap();
+    assert_eq!(stream.sender, yosemite);
+    assert_eq!(stream.recipient, everest);
+    assert_eq!(stream.rate_per_second, 10);
+    assert_eq!(stream.snapshot_time, e.ledger().timestamp());
+    assert!(!stream.is_paused);
+
+    // the stream draws from the sender's balance for the token
+    stream_client.deposit(&yosemite, &token_address, &1_000_000);
+    let balance = stream_client.get_sender_balance(&yosemite, &token_address);
+    assert_eq!(balance.balance, 1_000_000);
+    assert_eq!(balance.rate_per_second, 10);
+    assert_eq!(token_client.balance(&stream_address), 1_000_000);
+    assert_eq!(token_client.balance(&yosemite), 99_000_000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #203)")]
+fn test_create_invalid_rate() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let admin = Address::generate(&e);
+    let (stream_address, token_address) = create_flow_stream(&e, &admin);
+    let stream_client = FlowStreamContractClient::new(&e, &stream_address);
+
+    stream_client.create(&yosemite, &everest, &token_address, &0);
+}
@@ -0,0 +1,47 @@
+#[cfg(test)]
+use sep_41_token::testutils::MockTokenClient;
+use soroban_sdk::{testutils::Address as _, Address, Env, Error};
+use test_suites::{env::EnvTestUtils, flow_stream::create_flow_stream};
+use zentra_flow_stream::FlowStreamContractClient;
+
+#[test]
+fn test_pause_and_resume() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let admin = Address::generate(&e);
+    let (stream_address, token_address) = create_flow_stream(&e, &admin);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let stream_client = FlowStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &100_000_000);
+
+    let stream_id = stream_client.create(&yosemite, &everest, &token_address, &10);
+    stream_client.deposit(&yosemite, &token_address, &1_000_000);
+
+    // no debt accrues while paused, but accrued debt can still be withdrawn
+    e.jump(1_000);
+    stream_client.pause(&stream_id);
+    e.jump(1_000);
+    assert!(stream_client.get_stream(&stream_id).unwrap().is_paused);
+    assert_eq!(stream_client.total_debt(&stream_id), 50_000);
+    assert_eq!(
+        stream_client.withdraw_max(&everest, &stream_id, &everest),
+        50_000
+    );
+
+    let result = stream_client.try_pause(&stream_id);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(205))));
+
+    // debt accrues again from the time the stream is resumed
+    e.jump(1_000);
+    stream_client.resume(&stream_id);
+    e.jump(1_000);
+    assert_eq!(stream_client.total_debt(&stream_id), 50_000);
+
+    let result = stream_client.try_resume(&stream_id);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(206))));
+}
@@ -0,0 +1,125 @@
+#[cfg(test)]
+use sep_41_token::testutils::MockTokenClient;
+use soroban_sdk::{testutils::Address as _, Address, Env};
+use test_suites::{env::EnvTestUtils, flow_stream::create_flow_stream};
+use zentra_flow_stream::{types::Solvency, FlowStreamContractClient};
+
+#[test]
+fn test_solvency() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+    let now = e.ledger().timestamp();
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let admin = Address::generate(&e);
+    let (stream_address, token_address) = create_flow_stream(&e, &admin);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let stream_client = FlowStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &100_000_000);
+
+    let stream_id = stream_client.create(&yosemite, &everest, &token_address, &10);
+    stream_client.deposit(&yosemite, &token_address, &100_005);
+
+    // the balance covers 10_000 seconds of debt
+    e.jump(1_000);
+    assert_eq!(
+        stream_client.solvency(&yosemite, &token_address),
+        Solvency {
+            balance: 100_005,
+            total_debt: 50_000,
+            uncovered_debt: 0,
+            depletion_time: Some(now + 10_001),
+        }
+    );
+
+    e.jump(2_000);
+    assert_eq!(
+        stream_client.solvency(&yosemite, &token_address),
+        Solvency {
+            balance: 100_005,
+            total_debt: 150_000,
+            uncovered_debt: 49_995,
+            depletion_time: Some(now + 10_001),
+        }
+    );
+
+    // the debt of a paused stream does not grow
+    stream_client.pause(&stream_id);
+    assert_eq!(
+        stream_client
+            .solvency(&yosemite, &token_address)
+            .depletion_time,
+        None
+    );
+}
+
+#[test]
+fn test_solvency_shared_balance() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+    let now = e.ledger().timestamp();
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let denali = Address::generate(&e);
+    let admin = Address::generate(&e);
+    let (stream_address, token_address) = create_flow_stream(&e, &admin);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let stream_client = FlowStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &100_000_000);
+
+    let everest_stream_id = stream_client.create(&yosemite, &everest, &token_address, &10);
+    let denali_stream_id = stream_client.create(&yosemite, &denali, &token_address, &30);
+    stream_client.deposit(&yosemite, &token_address, &100_000);
+
+    // both streams draw from the same balance, so it covers 2_500 seconds of debt
+    e.jump(200);
+    assert_eq!(
+        stream_client.solvency(&yosemite, &token_address),
+        Solvency {
+            balance: 100_000,
+            total_debt: 40_000,
+            uncovered_debt: 0,
+            depletion_time: Some(now + 2_501),
+        }
+    );
+    assert_eq!(
+        stream_client.withdraw_max(&denali, &denali_stream_id, &denali),
+        30_000
+    );
+
+    // pausing a stream slows the depletion of the balance
+    stream_client.pause(&denali_stream_id);
+    let solvency = stream_client.solvency(&yosemite, &token_address);
+    assert_eq!(solvency.balance, 70_000);
+    assert_eq!(solvency.total_debt, 10_000);
+    assert_eq!(solvency.depletion_time, Some(now + 1_000 + 6_000 + 1));
+
+    // once the balance runs dry, streams are paid out in the order they withdraw
+    e.jump(1_500);
+    stream_client.resume(&denali_stream_id);
+    assert_eq!(stream_client.total_debt(&everest_stream_id), 85_000);
+    assert_eq!(
+        stream_client.withdrawable_amount(&everest_stream_id),
+        70_000
+    );
+    assert_eq!(
+        stream_client.withdraw_max(&everest, &everest_stream_id, &everest),
+        70_000
+    );
+    assert_eq!(stream_client.withdrawable_amount(&denali_stream_id), 0);
+    assert_eq!(
+        stream_client.solvency(&yosemite, &token_address),
+        Solvency {
+            balance: 0,
+            total_debt: 15_000,
+            uncovered_debt: 15_000,
+            depletion_time: Some(now + 8_500),
+        }
+    );
+}
@@ -0,0 +1,94 @@
+#[cfg(test)]
+use sep_41_token::testutils::MockTokenClient;
+use soroban_sdk::{testutils::Address as _, Address, Env, Error};
+use test_suites::{env::EnvTestUtils, flow_stream::create_flow_stream};
+use zentra_flow_stream::FlowStreamContractClient;
+
+#[test]
+fn test_withdraw() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let denali = Address::generate(&e);
+    let admin = Address::generate(&e);
+    let (stream_address, token_address) = create_flow_stream(&e, &admin);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let stream_client = FlowStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &100_000_000);
+
+    let stream_id = stream_client.create(&yosemite, &everest, &token_address, &10);
+    stream_client.deposit(&yosemite, &token_address, &100_000);
+
+    // 5_000 seconds at 10 per second
+    e.jump(1_000);
+    assert_eq!(stream_client.total_debt(&stream_id), 50_000);
+    assert_eq!(stream_client.withdrawable_amount(&stream_id), 50_000);
+
+    // the recipient can withdraw to any address
+    stream_client.withdraw(&everest, &denali, &stream_id, &20_000);
+    assert_eq!(token_client.balance(&denali), 20_000);
+    assert_eq!(stream_client.total_debt(&stream_id), 30_000);
+    assert_eq!(
+        stream_client
+            .get_sender_balance(&yosemite, &token_address)
+            .balance,
+        80_000
+    );
+
+    let result = stream_client.try_withdraw(&everest, &everest, &stream_id, &30_001);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(201))));
+
+    // the sender can only withdraw to the recipient
+    let result = stream_client.try_withdraw(&yosemite, &yosemite, &stream_id, &1);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(208))));
+    let result = stream_client.try_withdraw(&denali, &everest, &stream_id, &1);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(207))));
+}
+
+#[test]
+fn test_withdraw_accrues_debt_once_dry() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let admin = Address::generate(&e);
+    let (stream_address, token_address) = create_flow_stream(&e, &admin);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let stream_client = FlowStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &100_000_000);
+
+    let stream_id = stream_client.create(&yosemite, &everest, &token_address, &10);
+    stream_client.deposit(&yosemite, &token_address, &30_000);
+
+    // the balance only covers 30_000 of the 50_000 owed
+    e.jump(1_000);
+    assert_eq!(
+        stream_client.withdraw_max(&everest, &stream_id, &everest),
+        30_000
+    );
+    assert_eq!(stream_client.total_debt(&stream_id), 20_000);
+    assert_eq!(stream_client.withdrawable_amount(&stream_id), 0);
+
+    // debt keeps accruing and is paid out once the balance is topped up
+    e.jump(1_000);
+    stream_client.deposit(&yosemite, &token_address, &100_000);
+    assert_eq!(
+        stream_client.withdraw_max(&everest, &stream_id, &everest),
+        70_000
+    );
+    assert_eq!(token_client.balance(&everest), 100_000);
+    assert_eq!(stream_client.total_debt(&stream_id), 0);
+    assert_eq!(
+        stream_client
+            .get_sender_balance(&yosemite, &token_address)
+            .balance,
+        30_000
+    );
+}
@@ -0,0 +1,63 @@
+#[cfg(test)]
+use sep_41_token::testutils::MockTokenClient;
+use soroban_sdk::{testutils::Address as _, Address, Env};
+use test_suites::{env::EnvTestUtils, flow_stream::create_flow_stream};
+use zentra_flow_stream::FlowStreamContractClient;
+
+#[test]
+fn test_withdraw_balance() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let admin = Address::generate(&e);
+    let (stream_address, token_address) = create_flow_stream(&e, &admin);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let stream_client = FlowStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &100_000_000);
+
+    let stream_id = stream_client.create(&yosemite, &everest, &token_address, &10);
+    stream_client.deposit(&yosemite, &token_address, &100_000);
+
+    // 50_000 is owed to the recipient, so the sender can take back the rest
+    e.jump(1_000);
+    stream_client.withdraw_balance(&yosemite, &token_address, &50_000);
+    assert_eq!(token_client.balance(&yosemite), 100_000_000 - 50_000);
+    assert_eq!(
+        stream_client
+            .get_sender_balance(&yosemite, &token_address)
+            .balance,
+        50_000
+    );
+
+    assert_eq!(
+        stream_client.withdraw_max(&everest, &stream_id, &everest),
+        50_000
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #204)")]
+fn test_withdraw_balance_exceeds_refundable() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let yosemite = Address::generate(&e);
+    let everest = Address::generate(&e);
+    let admin = Address::generate(&e);
+    let (stream_address, token_address) = create_flow_stream(&e, &admin);
+    let token_client = MockTokenClient::new(&e, &token_address);
+    let stream_client = FlowStreamContractClient::new(&e, &stream_address);
+
+    token_client.mint(&yosemite, &100_000_000);
+
+    stream_client.create(&yosemite, &everest, &token_address, &10);
+    stream_client.deposit(&yosemite, &token_address, &100_000);
+
+    e.jump(1_000);
+    stream_client.withdraw_balance(&yosemite, &token_address, &50_001);
+}
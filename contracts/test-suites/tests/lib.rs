@@ -1 +1,2 @@
-mod direct_stream;
\ No newline at end of file
+mod direct_stream;
+mod flow_stream;
\ No newline at end of file